@@ -0,0 +1,203 @@
+use soroban_sdk::contracterror;
+
+/// Harcama, bakiye ve uzlaşma giriş noktalarının (ve okuma fonksiyonlarının) hata kodları.
+///
+/// Kodlar alan bazında gruplanmıştır ve sabittir; frontend/backend bu numaraları
+/// UI mesajlarına eşler. Yeni varyantlar ilgili aralığın sonuna eklenmeli,
+/// mevcut numaralar asla değiştirilmemeli ve kaldırılan numaralar yeniden kullanılmamalıdır.
+///
+/// Not: Kontrat spec'i bir hata enum'unda en fazla 50 varyanta izin verir. Bu yüzden
+/// grup yönetimi (`GroupError`), kontrat yönetimi (`AdminError`) ve çekirdekten bağımsız
+/// özellikler (`FeatureError`) kendi enum'larını döndürür. Numaralar tüm enum'larda
+/// ortaktır: birden çok enum'da geçen bir hata (ör. `GroupNotFound` = 100) her yerde
+/// aynı numarayı taşır ve hiçbir numara iki farklı anlamda kullanılmaz.
+#[contracterror]
+#[derive(Copy, Clone, Debug, Eq, PartialEq, PartialOrd, Ord)]
+#[repr(u32)]
+pub enum Error {
+    // ── Grup (1xx) ──
+    GroupNotFound = 100,
+    DuplicateMember = 104,
+    GroupAlreadySettled = 105,
+    NotAMember = 106,
+    Unauthorized = 108,
+    NotGroupAdmin = 109,
+    TokenNotAllowed = 111,
+
+    // ── Harcama (2xx) ──
+    ExpenseNotFound = 200,
+    InvalidAmount = 201,
+    PayerNotMember = 202,
+    EmptySplit = 203,
+    SplitMemberNotInGroup = 204,
+    NoExpensesToCancel = 205,
+    NotExpensePayer = 206,
+    SplitLengthMismatch = 207,
+    InvalidSplitValue = 208,
+    SplitSumMismatch = 209,
+    ExpenseDeleted = 210,
+    ExpenseNotPending = 211,
+    ExpenseDisputed = 212,
+    ExpenseNotDisputed = 213,
+    CategoryNotFound = 214,
+    RecurringNotFound = 217,
+    InvalidSchedule = 218,
+    InvalidReceiptUri = 219,
+    DescriptionTooLong = 220,
+    AlreadyApproved = 221,
+    TooManyRecurring = 222,
+    NoPayers = 223,
+    MultiPayerAmountLocked = 224,
+    TooManySplitMembers = 225,
+    TooManyPayers = 226,
+    CategoryTooLong = 227,
+
+    // ── Bakiye & Uzlaşma (3xx) ──
+    BalanceInvariantViolated = 300,
+    SelfPayment = 301,
+    PaymentExceedsDebt = 302,
+    PaymentNotFound = 303,
+    StalePlan = 304,
+}
+
+/// Grup yönetimi giriş noktalarının hata kodları: grup oluşturma, üyelik ve davetler,
+/// roller, token izin listesi, yuvarlama/onay politikası ve kategori kaydı.
+///
+/// `Error` ile ortak hatalar oradaki numarayı taşır.
+#[contracterror]
+#[derive(Copy, Clone, Debug, Eq, PartialEq, PartialOrd, Ord)]
+#[repr(u32)]
+pub enum GroupError {
+    // ── Grup & Üyelik (1xx) ──
+    GroupNotFound = 100,
+    GroupNameEmpty = 101,
+    GroupNameTooLong = 102,
+    NotEnoughMembers = 103,
+    DuplicateMember = 104,
+    GroupAlreadySettled = 105,
+    NotAMember = 106,
+    AlreadyAMember = 107,
+    Unauthorized = 108,
+    NotGroupAdmin = 109,
+    LastAdmin = 110,
+    TokenHasBalances = 112,
+    PrimaryTokenRequired = 113,
+    MemberHasBalance = 114,
+    InviteNotFound = 115,
+    InviteExpired = 116,
+    TooManyMembers = 117,
+
+    // ── Çekirdekle ortak (2xx / 3xx) ──
+    InvalidAmount = 201,
+    CategoryNotFound = 214,
+    CategoryTooLong = 227,
+    BalanceInvariantViolated = 300,
+
+    // ── Kategori Kaydı (8xx) ──
+    CategoryExists = 800,
+    InvalidCategory = 801,
+    TooManyCategories = 802,
+}
+
+/// Grup yönetimi, çekirdek yardımcılarından (`get_group`, admin kontrolü, bakiye
+/// önbelleği) gelen hataları aynı numarayla döndürür. Eşleme bilerek eksiksizdir:
+/// `Error`'a eklenen her varyant burada bir karar gerektirir.
+impl From<Error> for GroupError {
+    fn from(err: Error) -> Self {
+        match err {
+            Error::GroupNotFound => GroupError::GroupNotFound,
+            Error::DuplicateMember => GroupError::DuplicateMember,
+            Error::GroupAlreadySettled => GroupError::GroupAlreadySettled,
+            Error::NotAMember => GroupError::NotAMember,
+            Error::Unauthorized => GroupError::Unauthorized,
+            Error::NotGroupAdmin => GroupError::NotGroupAdmin,
+            Error::InvalidAmount => GroupError::InvalidAmount,
+            Error::CategoryNotFound => GroupError::CategoryNotFound,
+            Error::CategoryTooLong => GroupError::CategoryTooLong,
+            Error::BalanceInvariantViolated => GroupError::BalanceInvariantViolated,
+            // Bakiye yeniden hesabı kayıtlı harcamaları tekrar böler; bu hatalar
+            // orada ancak bozuk bir kayıttan doğabilir.
+            Error::ExpenseNotFound
+            | Error::PayerNotMember
+            | Error::EmptySplit
+            | Error::SplitMemberNotInGroup
+            | Error::SplitLengthMismatch
+            | Error::InvalidSplitValue
+            | Error::SplitSumMismatch => GroupError::BalanceInvariantViolated,
+            // Harcama yazımı, ödeme ve tekrarlayan şablon doğrulamaları; grup
+            // yönetimi bu yolları çağırmaz, çağırırsa tutarsız durum sayılır.
+            Error::TokenNotAllowed
+            | Error::NoExpensesToCancel
+            | Error::NotExpensePayer
+            | Error::ExpenseDeleted
+            | Error::ExpenseNotPending
+            | Error::ExpenseDisputed
+            | Error::ExpenseNotDisputed
+            | Error::RecurringNotFound
+            | Error::InvalidSchedule
+            | Error::InvalidReceiptUri
+            | Error::DescriptionTooLong
+            | Error::AlreadyApproved
+            | Error::TooManyRecurring
+            | Error::NoPayers
+            | Error::MultiPayerAmountLocked
+            | Error::TooManySplitMembers
+            | Error::TooManyPayers
+            | Error::SelfPayment
+            | Error::PaymentExceedsDebt
+            | Error::PaymentNotFound
+            | Error::StalePlan => GroupError::BalanceInvariantViolated,
+        }
+    }
+}
+
+/// Kontrat yönetimi giriş noktalarının (yönetici devri, yükseltme, migrate, ödül
+/// yapılandırması) hata kodları.
+#[contracterror]
+#[derive(Copy, Clone, Debug, Eq, PartialEq, PartialOrd, Ord)]
+#[repr(u32)]
+pub enum AdminError {
+    // ── Kontrat Yönetimi (6xx) ──
+    // 600 (`AlreadyInitialized`) yönetici constructor'da atandığı için kaldırıldı.
+    NotInitialized = 601,
+    NotContractAdmin = 602,
+    InvalidRewardConfig = 603,
+    NotPendingAdmin = 604,
+    /// Bir migrate adımı eski kaydı okuyamadı veya bakiyeleri doğrulayamadı.
+    MigrationFailed = 605,
+}
+
+/// Vasi, kasa ve kumbara giriş noktalarının hata kodları.
+///
+/// `Error` ile ortak hatalar oradaki numarayı taşır.
+#[contracterror]
+#[derive(Copy, Clone, Debug, Eq, PartialEq, PartialOrd, Ord)]
+#[repr(u32)]
+pub enum FeatureError {
+    // ── Çekirdekle ortak (1xx / 2xx) ──
+    GroupNotFound = 100,
+    NotAMember = 106,
+    InvalidAmount = 201,
+
+    // ── Vasi & Kurtarma (4xx) ──
+    NoGuardians = 400,
+    InvalidThreshold = 401,
+    GuardiansNotSet = 402,
+    NotAGuardian = 403,
+    RecoveryNotFound = 404,
+    RecoveryNotPending = 405,
+    AlreadyApproved = 406,
+    TooManyGuardians = 407,
+
+    // ── Kasa / Vault (5xx) ──
+    InsufficientVaultBalance = 500,
+    InsufficientYield = 501,
+
+    // ── Kumbara / Savings Pool (7xx) ──
+    PoolNotFound = 700,
+    PoolAlreadyActive = 701,
+    InvalidDeadline = 702,
+    PoolNotActive = 703,
+    PoolCancelled = 704,
+    NotPoolCreator = 705,
+}
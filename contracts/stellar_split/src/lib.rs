@@ -1,50 +1,368 @@
 #![no_std]
+// Soroban giriş noktaları (ve üretilen client) struct yerine düz argüman alır.
+#![allow(clippy::too_many_arguments)]
 
-use soroban_sdk::{contract, contractimpl, token, Address, Env, IntoVal, Map, String, Symbol, Vec};
+use soroban_sdk::xdr::ToXdr;
+use soroban_sdk::{contract, contractimpl, token, vec, Address, BytesN, Env, Map, String, Symbol, Vec};
 
+mod errors;
+mod reward;
 mod settle;
+mod split;
 mod storage;
+mod summary;
 mod types;
 
-use settle::compute_optimal_settlements;
+pub use errors::{AdminError, Error, FeatureError, GroupError};
+use reward::{compute_rewards, validate_reward_config};
+use settle::{compute_exit_settlements, solve_settlements};
+use summary::{apply_summary, empty_summary};
+use split::{apply_expense, apply_payment, balances_match, compute_split_amounts, ensure_zero_sum};
 use storage::{
-    get_expense, get_expenses_count, get_group, get_next_expense_id, get_next_group_id,
-    is_group_settled, remove_expense, save_expense, save_group, set_expenses_count, set_group_settled,
+    get_expense, get_frozen_expenses, get_expenses_count, get_group, get_next_expense_id, get_next_group_id,
+    is_group_settled, save_expense, save_group, set_expenses_count, set_group_settled,
     set_next_expense_id, set_next_group_id,
     get_guardian_config, save_guardian_config, get_recovery_request, save_recovery_request,
-    get_savings_pool, save_savings_pool,
+    get_savings_pool, save_savings_pool, get_group_balances, save_group_balances, get_invites, save_invites,
+    get_next_payment_id, set_next_payment_id, save_payment, get_payment,
+    get_admin, set_admin, get_reward_config, set_reward_config,
+    get_pending_admin, set_pending_admin, get_storage_version, set_storage_version, get_migration_cursor, set_migration_cursor, get_group_v0,
+    get_expense_summary, save_expense_summary, get_categories, save_categories,
+    get_recurring, save_recurring, get_next_recurring_id, set_next_recurring_id,
+    get_expense_v0, get_receipt, set_receipt, get_approval_policy, set_approval_policy, get_expense_approvals, save_expense_approvals,
 };
-use types::{Expense, Group, Settlement, GuardianConfig, RecoveryRequest, Vault, SavingsPool};
+use types::{ApprovalMode, ApprovalPolicy, CategoryTotals, Expense, ExpenseDetails, ExpenseFilter, ExpensePage, ExpenseStatus, ExpenseSummary, ExpenseV0, Group, GroupRole, Invite, LedgerStamp, MemberTotals, MigrationCursor, Payment, Receipt, RecurringExpense, RecurringSchedule, RewardConfig, RoundingPolicy, Settlement, SettlementPlan, SettlementSolver, SplitMode, GuardianConfig, RecoveryRequest, Vault, SavingsPool};
 
 // Stellar native token (XLM) SAC adresi — testnet
 // Gerçek deployda env üzerinden alınabilir.
 const MAX_GROUP_NAME_LEN: u32 = 64;
 const MIN_MEMBERS: u32 = 2;
+/// Bir grubun en fazla üye sayısı (bekleyen davetler dahil); `split_among` ve
+/// ödeyen listeleri de bu uzunlukla sınırlıdır (`TooManySplitMembers` / `TooManyPayers`).
+const MAX_MEMBERS: u32 = 50;
+/// Harcama açıklamasının en fazla uzunluğu (bayt).
+const MAX_DESCRIPTION_LEN: u32 = 128;
+/// Kategori id'si ve görünen adının en fazla uzunluğu (bayt).
+const MAX_CATEGORY_LEN: u32 = 32;
+/// Bir grubun kategori kaydındaki en fazla kategori (varsayılanlar dahil); kayıt
+/// tek bir girişte tutulur ve her harcama yazımında okunur.
+const MAX_CATEGORIES: u32 = 32;
+/// Bir kullanıcının en fazla vasi sayısı.
+const MAX_GUARDIANS: u32 = 10;
+/// Tek bir listeleme çağrısında okunabilecek en fazla harcama kaydı; seyrek
+/// eşleşen filtrelerde bütçeyi sınırlar (sayfa erken biter, `next` döner).
+/// Her eşleşen harcama makbuzu için bir okuma daha ekler: 40 kayıt + 40 makbuz,
+/// grup ve sayaç okumalarıyla işlem başına 100 okuma girişi sınırının altında kalır.
+const MAX_EXPENSE_SCAN: u64 = 40;
+/// `list_expenses` / `query_expenses` sayfa boyutu üst sınırı (taramadan büyük olamaz).
+const MAX_EXPENSE_PAGE: u32 = MAX_EXPENSE_SCAN as u32;
+/// Kategori kaydı hiç düzenlenmemiş gruplarda geçerli varsayılanlar: (id, görünen ad).
+const DEFAULT_CATEGORIES: [(&str, &str); 6] = [
+    ("food", "Yemek"),
+    ("transport", "Ulaşım"),
+    ("accommodation", "Konaklama"),
+    ("entertainment", "Eğlence"),
+    ("market", "Market"),
+    ("other", "Diğer"),
+];
+/// Tek bir `materialize_recurring` çağrısında oluşturulabilecek en fazla harcama;
+/// birikmiş dönemler sonraki çağrılarda tamamlanır.
+const MAX_MATERIALIZE_PER_CALL: u32 = 20;
+/// Bir grubun en fazla etkin tekrarlayan harcama şablonu; şablonlar tek bir girişte
+/// tutulur ve `materialize_recurring` her çağrıda hepsini dolaşır.
+const MAX_RECURRING_TEMPLATES: u32 = 20;
+/// Davetin kabul edilebileceği süre (saniye): 7 gün.
+const INVITE_TTL_SECONDS: u64 = 7 * 24 * 60 * 60;
+/// Makbuz URI'sinin (ör. `ipfs://<CID>` veya https adresi) en fazla uzunluğu.
+const MAX_RECEIPT_URI_LEN: u32 = 256;
+
+/// Depolama şemasının güncel sürümü. Yayınlanmış bir şemayı değiştiren her sürüm
+/// bunu artırır ve `migrate` içine bir adım ekler.
+pub const STORAGE_VERSION: u32 = 1;
+/// Tek bir `migrate` çağrısında yazılan en fazla kayıt (harcama veya grup). Önbellek
+/// ve instance yazımlarıyla birlikte işlem başına 50 yazma girişi sınırının altında kalır.
+const MAX_MIGRATION_BATCH: u32 = 25;
 
 #[contract]
 pub struct StellarSplitContract;
 
 #[contractimpl]
 impl StellarSplitContract {
+    // ─────────────────────────────────────────────
+    //  KONTRAT YÖNETİMİ
+    // ─────────────────────────────────────────────
+
+    /// Kontratı deploy anında kurar ve yöneticiyi atar. Global yapılandırma (ödül,
+    /// yükseltme vb.) sadece bu adres tarafından değiştirilebilir.
+    pub fn __constructor(env: Env, admin: Address) {
+        set_admin(&env, &admin);
+        // Boş depolama güncel şemayla başlar; grubu olan depolama `migrate` bekler
+        if get_next_group_id(&env) == 0 {
+            set_storage_version(&env, STORAGE_VERSION);
+        }
+
+        env.events().publish((Symbol::new(&env, "initialized"),), admin);
+    }
+
+    /// Kontrat yöneticisini döndürür.
+    pub fn get_admin(env: Env) -> Result<Address, AdminError> {
+        get_admin(&env).ok_or(AdminError::NotInitialized)
+    }
+
+    /// Yönetici devrinin ilk adımı: yeni adresi bekleyen yönetici olarak kaydeder.
+    /// Devir, yeni adres `accept_admin` çağırana kadar tamamlanmaz.
+    pub fn propose_admin(env: Env, caller: Address, new_admin: Address) -> Result<(), AdminError> {
+        Self::require_contract_admin(&env, &caller)?;
+        set_pending_admin(&env, &Some(new_admin.clone()));
+
+        env.events().publish(
+            (Symbol::new(&env, "admin_proposed"), caller),
+            new_admin,
+        );
+        Ok(())
+    }
+
+    /// Yönetici devrinin ikinci adımı: bekleyen yönetici devri kabul eder.
+    pub fn accept_admin(env: Env, new_admin: Address) -> Result<(), AdminError> {
+        new_admin.require_auth();
+        let pending = get_pending_admin(&env).ok_or(AdminError::NotPendingAdmin)?;
+        if pending != new_admin {
+            return Err(AdminError::NotPendingAdmin);
+        }
+        let old_admin = get_admin(&env).ok_or(AdminError::NotInitialized)?;
+        set_admin(&env, &new_admin);
+        set_pending_admin(&env, &None);
+
+        env.events().publish(
+            (Symbol::new(&env, "admin_transferred"), old_admin),
+            new_admin,
+        );
+        Ok(())
+    }
+
+    /// Devri kabul bekleyen yöneticiyi döndürür.
+    pub fn get_pending_admin(env: Env) -> Option<Address> {
+        get_pending_admin(&env)
+    }
+
+    /// Kontrat kodunu yüklenmiş yeni bir Wasm ile değiştirir. Sadece yönetici.
+    /// Depolama korunur; şema değiştiyse ardından `migrate` çağrılmalıdır.
+    pub fn upgrade(env: Env, caller: Address, new_wasm_hash: BytesN<32>) -> Result<(), AdminError> {
+        Self::require_contract_admin(&env, &caller)?;
+        env.deployer().update_current_contract_wasm(new_wasm_hash.clone());
+
+        env.events().publish((Symbol::new(&env, "upgraded"),), new_wasm_hash);
+        Ok(())
+    }
+
+    /// Depolamayı `STORAGE_VERSION`'a taşır. Taşıma partiler halinde ilerler: her çağrı
+    /// en fazla `limit` (1..=25) kaydı çevirir ve kaldığı konumu saklar. Devam konumunu,
+    /// taşıma bittiyse `None` döndürür; sürüm ancak tüm gruplar taşındığında artar.
+    /// Güncel şemada çağrı etkisizdir.
+    ///
+    /// Yöneticisi olmayan eski (v0) kurulumda ilk çağrıyı 0 numaralı grubun kurucusu
+    /// (ilk üyesi) imzalar ve kontrat yöneticisi olur.
+    pub fn migrate(env: Env, caller: Address, limit: u32) -> Result<Option<MigrationCursor>, AdminError> {
+        if get_admin(&env).is_none() {
+            Self::claim_legacy_admin(&env, &caller)?;
+        } else {
+            Self::require_contract_admin(&env, &caller)?;
+        }
+        let from = get_storage_version(&env);
+        if from >= STORAGE_VERSION {
+            return Ok(None);
+        }
+
+        let cursor = Self::migrate_v0_to_v1(&env, limit.clamp(1, MAX_MIGRATION_BATCH))
+            .map_err(|_| AdminError::MigrationFailed)?;
+        set_migration_cursor(&env, &cursor);
+        if cursor.is_none() {
+            set_storage_version(&env, STORAGE_VERSION);
+            env.events().publish(
+                (Symbol::new(&env, "migrated"), from),
+                STORAGE_VERSION,
+            );
+        }
+        Ok(cursor)
+    }
+
+    /// Depolama şeması sürümünü döndürür.
+    pub fn get_storage_version(env: Env) -> u32 {
+        get_storage_version(&env)
+    }
+
+    /// Yarım kalmış taşımanın devam konumunu döndürür (taşıma yoksa `None`).
+    pub fn get_migration_cursor(env: Env) -> Option<MigrationCursor> {
+        get_migration_cursor(&env)
+    }
+
+    /// Constructor'ı hiç çalışmamış (yöneticisiz) v0 kurulumunda yöneticiyi atar.
+    /// İlk sürüm grubu kuranı kaydetmez; kurucu, 0 numaralı grubun ilk üyesidir.
+    fn claim_legacy_admin(env: &Env, caller: &Address) -> Result<(), AdminError> {
+        caller.require_auth();
+        if get_storage_version(env) != 0 || get_migration_cursor(env).is_some() {
+            return Err(AdminError::NotInitialized);
+        }
+        let founder = get_group_v0(env, 0)
+            .and_then(|g| g.members.get(0))
+            .ok_or(AdminError::NotInitialized)?;
+        if *caller != founder {
+            return Err(AdminError::NotContractAdmin);
+        }
+        set_admin(env, caller);
+
+        env.events().publish((Symbol::new(env, "initialized"),), caller.clone());
+        Ok(())
+    }
+
+    /// v0 → v1: ilk sürümün grup ve harcamaları güncel düzene taşınır; bakiye ve özet
+    /// önbellekleri aynı geçişte kurulur. Kurucu kaydedilmediği için ilk üye owner/admin
+    /// olur; harcamalar grubun token'ıyla eşit bölünmüş (kalan ödeyene), aktif ve payer
+    /// tarafından kaydedilmiş sayılır. Oluşturulma zamanları bilinmez: damgalar sıfırdır.
+    ///
+    /// Bir grubun harcamaları birkaç çağrıya yayılabilir; grup kaydı son harcamasından
+    /// sonra yeni düzene geçer. En fazla `limit` kayıt (harcama veya grup) yazılır.
+    fn migrate_v0_to_v1(env: &Env, limit: u32) -> Result<Option<MigrationCursor>, Error> {
+        let mut cursor = get_migration_cursor(env).unwrap_or(MigrationCursor { group_id: 0, expense_id: 0 });
+        let mut budget = limit;
+        let group_count = get_next_group_id(env);
+
+        while cursor.group_id < group_count {
+            if budget == 0 {
+                return Ok(Some(cursor));
+            }
+            let group_id = cursor.group_id;
+            let old = match get_group_v0(env, group_id) {
+                Some(g) => g,
+                None => {
+                    cursor = MigrationCursor { group_id: group_id + 1, expense_id: 0 };
+                    continue;
+                }
+            };
+            let owner = old.members.get(0).unwrap();
+            let group = Group {
+                id: old.id,
+                name: old.name,
+                owner: owner.clone(),
+                admins: vec![env, owner],
+                members: old.members,
+                tokens: vec![env, old.token.clone()],
+                token: old.token,
+                expense_count: old.expense_count,
+                rounding: RoundingPolicy::Payer,
+            };
+
+            let mut balances = get_group_balances(env, group_id, &group.token).unwrap_or(Map::new(env));
+            let mut summary = get_expense_summary(env, group_id, &group.token).unwrap_or(empty_summary(env));
+            let end = get_next_expense_id(env, group_id);
+            while cursor.expense_id < end && budget > 0 {
+                if let Some(old) = get_expense_v0(env, group_id, cursor.expense_id) {
+                    let expense = Self::expense_from_v0(env, &group, old);
+                    apply_expense(env, &mut balances, &expense, 1)?;
+                    apply_summary(env, &mut summary, &expense, 1)?;
+                    save_expense(env, group_id, cursor.expense_id, &expense);
+                }
+                cursor.expense_id += 1;
+                budget -= 1;
+            }
+            save_group_balances(env, group_id, &group.token, &balances);
+            save_expense_summary(env, group_id, &group.token, &summary);
+
+            if cursor.expense_id < end || budget == 0 {
+                return Ok(Some(cursor));
+            }
+            ensure_zero_sum(&balances)?;
+            save_group(env, group_id, &group);
+            budget -= 1;
+            cursor = MigrationCursor { group_id: group_id + 1, expense_id: 0 };
+        }
+        Ok(None)
+    }
+
+    /// v0 düzenindeki harcamayı güncel düzene çevirir (grubun token'ı, eşit bölüşüm).
+    fn expense_from_v0(env: &Env, group: &Group, old: ExpenseV0) -> Expense {
+        let unknown = LedgerStamp { timestamp: 0, sequence: 0 };
+        Expense {
+            id: old.id,
+            submitter: old.payer.clone(),
+            contributions: Map::from_array(env, [(old.payer.clone(), old.amount)]),
+            payer: old.payer,
+            amount: old.amount,
+            token: group.token.clone(),
+            split_among: old.split_among,
+            description: old.description,
+            category: old.category,
+            split_mode: SplitMode::Equal,
+            split_values: Vec::new(env),
+            rounding: group.rounding,
+            deleted: false,
+            status: ExpenseStatus::Active,
+            created: unknown.clone(),
+            modified: unknown,
+            occurred_at: None,
+        }
+    }
+
+    /// Settle ödülü yapılandırmasını ayarlar. Sadece kontrat yöneticisi.
+    ///
+    /// Ödül token'ı bu kontratın mint yetkisi olduğu bir token olmalıdır
+    /// (SPLT'de admin, SAC'de asset admin).
+    pub fn set_reward_config(env: Env, caller: Address, config: RewardConfig) -> Result<(), AdminError> {
+        Self::require_contract_admin(&env, &caller)?;
+        validate_reward_config(&config)?;
+        set_reward_config(&env, &Some(config.clone()));
+
+        env.events().publish(
+            (Symbol::new(&env, "reward_config_set"), caller),
+            config,
+        );
+        Ok(())
+    }
+
+    /// Ödül yapılandırmasını siler; sonraki settle'lar ödülsüz yapılır.
+    pub fn clear_reward_config(env: Env, caller: Address) -> Result<(), AdminError> {
+        Self::require_contract_admin(&env, &caller)?;
+        set_reward_config(&env, &None);
+
+        env.events().publish((Symbol::new(&env, "reward_config_cleared"),), caller);
+        Ok(())
+    }
+
+    /// Geçerli ödül yapılandırmasını döndürür (yoksa `None`).
+    pub fn get_reward_config(env: Env) -> Option<RewardConfig> {
+        get_reward_config(&env)
+    }
+
+    /// Çağıranın kontrat yöneticisi olduğunu doğrular.
+    fn require_contract_admin(env: &Env, caller: &Address) -> Result<(), AdminError> {
+        caller.require_auth();
+        let admin = get_admin(env).ok_or(AdminError::NotInitialized)?;
+        if *caller != admin {
+            return Err(AdminError::NotContractAdmin);
+        }
+        Ok(())
+    }
+
     // ─────────────────────────────────────────────
     //  GRUP OLUŞTURMA
     // ─────────────────────────────────────────────
 
     /// Yeni bir grup oluşturur.
-    /// Validasyonlar: isim uzunluğu, minimum 2 üye, duplicate üye kontrolü.
+    /// Validasyonlar: isim uzunluğu, 2–50 üye (davetliler dahil), duplicate üye kontrolü.
+    /// Kurucu tek aktif üye olarak başlar; diğer herkese davet gönderilir.
     /// Döndürdüğü değer: group_id
-    pub fn create_group(env: Env, creator: Address, name: String, members: Vec<Address>, token: Address) -> u64 {
+    pub fn create_group(env: Env, creator: Address, name: String, members: Vec<Address>, token: Address) -> Result<u64, GroupError> {
         creator.require_auth();
 
         // ── Validasyonlar ──
 
         // İsim boş olmamalı
         if name.is_empty() {
-            panic!("group name cannot be empty");
+            return Err(GroupError::GroupNameEmpty);
         }
         // İsim çok uzun olmamalı
         if name.len() > MAX_GROUP_NAME_LEN {
-            panic!("group name too long (max 64 chars)");
+            return Err(GroupError::GroupNameTooLong);
         }
 
         // Creator'ı ekle (yoksa)
@@ -60,282 +378,1683 @@ impl StellarSplitContract {
             all_members.push_back(creator.clone());
         }
 
-        // Minimum üye sayısı
+        // Minimum / maksimum üye sayısı
         if all_members.len() < MIN_MEMBERS {
-            panic!("at least 2 members required");
+            return Err(GroupError::NotEnoughMembers);
+        }
+        if all_members.len() > MAX_MEMBERS {
+            return Err(GroupError::TooManyMembers);
         }
 
         // Duplicate üye kontrolü
         for i in 0..all_members.len() {
             for j in (i + 1)..all_members.len() {
                 if all_members.get(i).unwrap() == all_members.get(j).unwrap() {
-                    panic!("duplicate member detected");
+                    return Err(GroupError::DuplicateMember);
                 }
             }
         }
 
         // ── Oluştur & Kaydet ──
+        // Kurucu dışındaki herkes davet edilir; `accept_invite` ile kabul edene kadar üye sayılmaz
         let group_id = get_next_group_id(&env);
         let group = Group {
             id: group_id,
             name,
-            members: all_members,
+            members: vec![&env, creator.clone()],
+            owner: creator.clone(),
+            admins: vec![&env, creator.clone()],
+            tokens: vec![&env, token.clone()],
             token,
             expense_count: 0,
+            rounding: RoundingPolicy::Payer,
+        };
+
+        save_group(&env, group_id, &group);
+        set_next_group_id(&env, group_id + 1);
+        set_expenses_count(&env, group_id, 0);
+        save_group_balances(&env, group_id, &group.token, &Map::new(&env));
+
+        // ── Event Yayınla ──
+        env.events().publish(
+            (Symbol::new(&env, "group_created"), group_id),
+            creator.clone(),
+        );
+
+        let mut invites = Map::new(&env);
+        for member in all_members.iter() {
+            if member != creator {
+                Self::send_invite(&env, group_id, &mut invites, &creator, &member);
+            }
+        }
+        save_invites(&env, group_id, &invites);
+
+        Ok(group_id)
+    }
+
+    // ─────────────────────────────────────────────
+    //  HARCAMA EKLEME
+    // ─────────────────────────────────────────────
+
+    /// Gruba yeni bir harcama ekler; tutar `split_among` arasında eşit bölünür.
+    /// Validasyonlar: pozitif tutar, payer grupta, split_among grupta, boş olmayan split.
+    /// Harcamayı payer kendisi kaydeder; başkası adına kayıt için `add_split_expense`.
+    pub fn add_expense(
+        env: Env,
+        group_id: u64,
+        payer: Address,
+        amount: i128,
+        split_among: Vec<Address>,
+        description: String,
+        category: String,
+    ) -> Result<u64, Error> {
+        let group = get_group(&env, group_id)?;
+        Self::add_split_expense(
+            env.clone(),
+            group_id,
+            payer.clone(),
+            payer,
+            amount,
+            group.token,
+            split_among,
+            SplitMode::Equal,
+            Vec::new(&env),
+            ExpenseDetails { description, category, occurred_at: None },
+        )
+    }
+
+    /// Gruba belirli bir token ve bölüşüm moduyla harcama ekler.
+    /// `token` grubun izin listesinde olmalı (bkz. `set_group_token`).
+    /// `split_values`, `split_among` ile aynı sırada olmalı (bkz. `SplitMode`);
+    /// Percentage için toplam 10_000 baz puan, Exact için toplam `amount` olmalı.
+    /// `submitter` harcamayı kaydeden üyedir (payer'dan farklı olabilir); o ve payer imzalar.
+    pub fn add_split_expense(
+        env: Env,
+        group_id: u64,
+        submitter: Address,
+        payer: Address,
+        amount: i128,
+        token: Address,
+        split_among: Vec<Address>,
+        split_mode: SplitMode,
+        split_values: Vec<i128>,
+        details: ExpenseDetails,
+    ) -> Result<u64, Error> {
+        // Aynı adresten iki kez imza istenemez
+        payer.require_auth();
+        if submitter != payer {
+            submitter.require_auth();
+        }
+
+        // Grup var mı + settled kontrolü
+        let group = get_group(&env, group_id)?;
+
+        if is_group_settled(&env, group_id) {
+            return Err(Error::GroupAlreadySettled);
+        }
+
+        let now = Self::ledger_stamp(&env);
+        let expense = Expense {
+            id: 0,
+            contributions: Map::from_array(&env, [(payer.clone(), amount)]),
+            submitter,
+            payer,
+            amount,
+            token,
+            split_among,
+            description: details.description,
+            category: details.category,
+            split_mode,
+            split_values,
+            rounding: group.rounding,
+            deleted: false,
+            status: ExpenseStatus::Active,
+            created: now.clone(),
+            modified: now,
+            occurred_at: details.occurred_at,
+        };
+        Self::validate_new_expense(&env, group_id, &group, &expense)?;
+        // Kaydeden üye olmalı (payer'ın üyeliği yukarıda doğrulandı)
+        if !group.members.contains(&expense.submitter) {
+            return Err(Error::NotAMember);
+        }
+        Self::record_expense(&env, group_id, expense)
+    }
+
+    /// Birden fazla kişinin ödediği harcama (ör. iki kartla ödenen hesap).
+    /// `payers` ve `contributions` aynı sırada olmalı; tutar katkıların toplamıdır.
+    /// Her payer ve (payer değilse) `submitter` imzalamalıdır; ilk payer birincil
+    /// payer olur (düzenleme / silme).
+    pub fn add_multi_payer_expense(
+        env: Env,
+        group_id: u64,
+        submitter: Address,
+        payers: Vec<Address>,
+        contributions: Vec<i128>,
+        token: Address,
+        split_among: Vec<Address>,
+        split_mode: SplitMode,
+        split_values: Vec<i128>,
+        details: ExpenseDetails,
+    ) -> Result<u64, Error> {
+        // Aynı adresten iki kez imza istenemez: tekrarlar imzadan önce reddedilir
+        if payers.len() != contributions.len() {
+            return Err(Error::SplitLengthMismatch);
+        }
+        if payers.len() > MAX_MEMBERS {
+            return Err(Error::TooManyPayers);
+        }
+        let primary = payers.first().ok_or(Error::NoPayers)?;
+        let mut paid: Map<Address, i128> = Map::new(&env);
+        let mut amount: i128 = 0;
+        for (payer, contribution) in payers.iter().zip(contributions.iter()) {
+            if paid.contains_key(payer.clone()) {
+                return Err(Error::DuplicateMember);
+            }
+            paid.set(payer, contribution);
+            amount = amount.checked_add(contribution).ok_or(Error::InvalidAmount)?;
+        }
+        for payer in payers.iter() {
+            payer.require_auth();
+        }
+        if !paid.contains_key(submitter.clone()) {
+            submitter.require_auth();
+        }
+
+        let group = get_group(&env, group_id)?;
+        if is_group_settled(&env, group_id) {
+            return Err(Error::GroupAlreadySettled);
+        }
+
+        let now = Self::ledger_stamp(&env);
+        let expense = Expense {
+            id: 0,
+            submitter,
+            payer: primary,
+            amount,
+            token,
+            split_among,
+            description: details.description,
+            category: details.category,
+            split_mode,
+            split_values,
+            rounding: group.rounding,
+            deleted: false,
+            status: ExpenseStatus::Active,
+            contributions: paid,
+            created: now.clone(),
+            modified: now,
+            occurred_at: details.occurred_at,
+        };
+        Self::validate_new_expense(&env, group_id, &group, &expense)?;
+        // Kaydeden üye olmalı (payer'ın üyeliği yukarıda doğrulandı)
+        if !group.members.contains(&expense.submitter) {
+            return Err(Error::NotAMember);
+        }
+        Self::record_expense(&env, group_id, expense)
+    }
+
+    /// Yeni harcamanın token'ını, ödeyenlerini, alanlarını ve kategorisini doğrular.
+    fn validate_new_expense(env: &Env, group_id: u64, group: &Group, expense: &Expense) -> Result<(), Error> {
+        if !group.tokens.contains(&expense.token) {
+            return Err(Error::TokenNotAllowed);
+        }
+        for (payer, paid) in expense.contributions.iter() {
+            if !group.members.contains(&payer) {
+                return Err(Error::PayerNotMember);
+            }
+            if paid <= 0 {
+                return Err(Error::InvalidAmount);
+            }
+        }
+        if expense.description.len() > MAX_DESCRIPTION_LEN {
+            return Err(Error::DescriptionTooLong);
+        }
+        Self::validate_expense(
+            env,
+            group,
+            &expense.payer,
+            expense.amount,
+            &expense.split_among,
+            expense.split_mode,
+            &expense.split_values,
+        )?;
+        Self::require_category(env, group_id, &expense.category)
+    }
+
+    /// Doğrulanmış harcamayı kaydeder: id ve onay durumu burada atanır,
+    /// önbellekler ve sayaçlar güncellenir, `expense_added` yayınlanır.
+    fn record_expense(env: &Env, group_id: u64, mut expense: Expense) -> Result<u64, Error> {
+        let expense_id = get_next_expense_id(env, group_id);
+        expense.id = expense_id;
+        expense.status = Self::initial_status(env, group_id, &expense);
+
+        save_expense(env, group_id, expense_id, &expense);
+        set_next_expense_id(env, group_id, expense_id + 1);
+
+        // Net bakiye önbelleğini artımlı güncelle (onay bekleyen harcama katılmaz)
+        Self::apply_cached(env, group_id, &expense, 1)?;
+
+        // Expense count güncelle (storage + Group struct böylece get_group doğru sayıyı döner)
+        let count = get_expenses_count(env, group_id);
+        set_expenses_count(env, group_id, count + 1);
+        let mut group = get_group(env, group_id)?;
+        group.expense_count = get_next_expense_id(env, group_id);
+        save_group(env, group_id, &group);
+
+        // ── Event Yayınla ──
+        env.events().publish(
+            (Symbol::new(env, "expense_added"), group_id, expense_id),
+            (expense.payer.clone(), expense.amount),
+        );
+        if expense.status == ExpenseStatus::Pending {
+            env.events().publish(
+                (Symbol::new(env, "expense_pending"), group_id, expense_id),
+                Self::affected_members(env, &expense),
+            );
+        }
+
+        Ok(expense_id)
+    }
+
+    /// Harcama alanlarını doğrular: payer grupta, pozitif tutar, boş olmayan
+    /// ve tamamı grupta olan split, moda uygun bölüşüm değerleri.
+    fn validate_expense(
+        env: &Env,
+        group: &Group,
+        payer: &Address,
+        amount: i128,
+        split_among: &Vec<Address>,
+        split_mode: SplitMode,
+        split_values: &Vec<i128>,
+    ) -> Result<(), Error> {
+        // Payer grupta mı
+        let mut payer_in_group = false;
+        for i in 0..group.members.len() {
+            if group.members.get(i).unwrap() == *payer {
+                payer_in_group = true;
+                break;
+            }
+        }
+        if !payer_in_group {
+            return Err(Error::PayerNotMember);
+        }
+
+        // Amount pozitif olmalı
+        if amount <= 0 {
+            return Err(Error::InvalidAmount);
+        }
+
+        // split_among boş olmamalı ve grup üye sınırını aşmamalı
+        if split_among.is_empty() {
+            return Err(Error::EmptySplit);
+        }
+        if split_among.len() > MAX_MEMBERS {
+            return Err(Error::TooManySplitMembers);
+        }
+
+        // split_among içindeki herkes grupta olmalı
+        for i in 0..split_among.len() {
+            let addr = split_among.get(i).unwrap();
+            let mut found = false;
+            for j in 0..group.members.len() {
+                if group.members.get(j).unwrap() == addr {
+                    found = true;
+                    break;
+                }
+            }
+            if !found {
+                return Err(Error::SplitMemberNotInGroup);
+            }
+        }
+
+        // Bölüşüm değerleri moda uygun mu (uzunluk, pozitiflik, toplam)
+        compute_split_amounts(env, amount, split_mode, split_among, split_values)?;
+        Ok(())
+    }
+
+    // ─────────────────────────────────────────────
+    //  HARCAMA DÜZENLEME / SİLME (payer veya grup admini)
+    // ─────────────────────────────────────────────
+
+    /// Mevcut bir harcamanın tutarını, bölüşümünü, açıklamasını ve kategorisini günceller.
+    /// Sadece harcamanın payer'ı veya bir grup admini çağırabilir; silinmiş harcamalar düzenlenemez.
+    /// `expense_updated` eventi eski ve yeni harcamayı birlikte taşır.
+    pub fn update_expense(
+        env: Env,
+        group_id: u64,
+        expense_id: u64,
+        caller: Address,
+        amount: i128,
+        split_among: Vec<Address>,
+        split_mode: SplitMode,
+        split_values: Vec<i128>,
+        description: String,
+        category: String,
+    ) -> Result<Expense, Error> {
+        caller.require_auth();
+
+        let group = get_group(&env, group_id)?;
+        if is_group_settled(&env, group_id) {
+            return Err(Error::GroupAlreadySettled);
+        }
+
+        let old = get_expense(&env, group_id, expense_id)?;
+        if old.deleted {
+            return Err(Error::ExpenseDeleted);
+        }
+        if old.payer != caller && !group.admins.contains(&caller) {
+            return Err(Error::Unauthorized);
+        }
+
+        Self::validate_expense(&env, &group, &old.payer, amount, &split_among, split_mode, &split_values)?;
+        if description.len() > MAX_DESCRIPTION_LEN {
+            return Err(Error::DescriptionTooLong);
+        }
+        if category != old.category {
+            Self::require_category(&env, group_id, &category)?;
+        }
+
+        // Tek payer'lı harcamada katkı tutarı izler; çok payer'lıda katkılar sabit
+        // kalır, bu yüzden toplam tutar değiştirilemez (sil ve yeniden ekle)
+        let contributions = if old.contributions.len() <= 1 {
+            Map::from_array(&env, [(old.payer.clone(), amount)])
+        } else if amount == old.amount {
+            old.contributions.clone()
+        } else {
+            return Err(Error::MultiPayerAmountLocked);
         };
 
+        let mut updated = old.clone();
+        updated.amount = amount;
+        updated.contributions = contributions;
+        updated.split_among = split_among;
+        updated.split_mode = split_mode;
+        updated.split_values = split_values;
+        updated.description = description;
+        updated.category = category;
+        updated.modified = Self::ledger_stamp(&env);
+        // Değişen harcama yeniden onaya tabidir; önceki onaylar ve itiraz düşer
+        updated.status = Self::initial_status(&env, group_id, &updated);
+
+        Self::apply_cached(&env, group_id, &old, -1)?;
+        Self::apply_cached(&env, group_id, &updated, 1)?;
+        save_expense(&env, group_id, expense_id, &updated);
+        save_expense_approvals(&env, group_id, expense_id, &Vec::new(&env));
+
+        env.events().publish(
+            (Symbol::new(&env, "expense_updated"), group_id, expense_id),
+            (caller, old, updated.clone()),
+        );
+
+        Ok(updated)
+    }
+
+    /// Bir harcamayı siler (tombstone). Kayıt silinmez, `deleted = true` olarak
+    /// işaretlenir; böylece expense id'leri sabit kalır ve bakiyelere katılmaz.
+    /// Sadece harcamanın payer'ı veya bir grup admini çağırabilir.
+    pub fn delete_expense(env: Env, group_id: u64, expense_id: u64, caller: Address) -> Result<(), Error> {
+        caller.require_auth();
+
+        let group = get_group(&env, group_id)?;
+        if is_group_settled(&env, group_id) {
+            return Err(Error::GroupAlreadySettled);
+        }
+
+        let expense = get_expense(&env, group_id, expense_id)?;
+        if expense.deleted {
+            return Err(Error::ExpenseDeleted);
+        }
+        if expense.payer != caller && !group.admins.contains(&caller) {
+            return Err(Error::Unauthorized);
+        }
+
+        Self::tombstone_expense(&env, group_id, expense_id, expense, caller)
+    }
+
+    /// Doğrulanmış harcamayı bakiyelerden düşer, `deleted` olarak işaretler ve
+    /// `expense_deleted` yayınlar. Kayıt ve id yerinde kalır.
+    fn tombstone_expense(env: &Env, group_id: u64, expense_id: u64, mut expense: Expense, caller: Address) -> Result<(), Error> {
+        Self::apply_cached(env, group_id, &expense, -1)?;
+
+        expense.deleted = true;
+        expense.modified = Self::ledger_stamp(env);
+        save_expense(env, group_id, expense_id, &expense);
+        save_expense_approvals(env, group_id, expense_id, &Vec::new(env));
+
+        let count = get_expenses_count(env, group_id);
+        set_expenses_count(env, group_id, count - 1);
+
+        env.events().publish(
+            (Symbol::new(env, "expense_deleted"), group_id, expense_id),
+            (caller, expense),
+        );
+
+        Ok(())
+    }
+
+    /// Harcamanın kullanıcı tarafından bildirilen zamanını (`occurred_at`, Unix saniye)
+    /// ayarlar veya `None` ile temizler. Bakiyeleri etkilemez, onay durumunu değiştirmez.
+    /// Harcamanın payer'ı, kaydedeni veya bir grup admini çağırabilir; settle edilmiş
+    /// grupta değiştirilemez (`GroupAlreadySettled`).
+    pub fn set_expense_date(
+        env: Env,
+        group_id: u64,
+        expense_id: u64,
+        caller: Address,
+        occurred_at: Option<u64>,
+    ) -> Result<Expense, Error> {
+        caller.require_auth();
+
+        let group = get_group(&env, group_id)?;
+        if is_group_settled(&env, group_id) {
+            return Err(Error::GroupAlreadySettled);
+        }
+        let mut expense = get_expense(&env, group_id, expense_id)?;
+        if expense.deleted {
+            return Err(Error::ExpenseDeleted);
+        }
+        if expense.payer != caller && expense.submitter != caller && !group.admins.contains(&caller) {
+            return Err(Error::Unauthorized);
+        }
+
+        expense.occurred_at = occurred_at;
+        expense.modified = Self::ledger_stamp(&env);
+        save_expense(&env, group_id, expense_id, &expense);
+
+        env.events().publish(
+            (Symbol::new(&env, "expense_dated"), group_id, expense_id),
+            (caller, occurred_at),
+        );
+        Ok(expense)
+    }
+
+    /// Harcamaya makbuz ekler veya mevcut makbuzu değiştirir: dosyanın SHA-256
+    /// özeti ve adresi (boş olmayan, en fazla 256 karakter URI / IPFS CID).
+    /// Sadece harcamanın payer'ı çağırabilir. `receipt_attached` eventi yeni
+    /// makbuzu ve (varsa) değiştirilen makbuzun özetini taşır; depolama servisi
+    /// dosyayı buna göre sabitler.
+    pub fn attach_receipt(
+        env: Env,
+        group_id: u64,
+        expense_id: u64,
+        payer: Address,
+        hash: BytesN<32>,
+        uri: String,
+    ) -> Result<Expense, Error> {
+        payer.require_auth();
+
+        get_group(&env, group_id)?;
+        if is_group_settled(&env, group_id) {
+            return Err(Error::GroupAlreadySettled);
+        }
+        let mut expense = get_expense(&env, group_id, expense_id)?;
+        if expense.deleted {
+            return Err(Error::ExpenseDeleted);
+        }
+        if expense.payer != payer {
+            return Err(Error::NotExpensePayer);
+        }
+        if uri.is_empty() || uri.len() > MAX_RECEIPT_URI_LEN {
+            return Err(Error::InvalidReceiptUri);
+        }
+
+        let replaced = get_receipt(&env, group_id, expense_id).map(|r| r.hash);
+        let receipt = Receipt { hash, uri };
+        set_receipt(&env, group_id, expense_id, &Some(receipt.clone()));
+        expense.modified = Self::ledger_stamp(&env);
+        save_expense(&env, group_id, expense_id, &expense);
+
+        env.events().publish(
+            (Symbol::new(&env, "receipt_attached"), group_id, expense_id),
+            (receipt, replaced),
+        );
+        Ok(expense)
+    }
+
+    // ─────────────────────────────────────────────
+    //  SON HARCAMAYI İPTAL (sadece ödeyen, settle öncesi)
+    // ─────────────────────────────────────────────
+
+    /// Son eklenen harcamayı iptal eder. Harcamanın payer'ı veya bir grup admini çağırabilir; grup settle edilmemiş olmalı.
+    /// `delete_expense` gibi tombstone bırakır ve `expense_deleted` yayınlar; id yeniden kullanılmaz.
+    pub fn cancel_last_expense(env: Env, group_id: u64, caller: Address) -> Result<(), Error> {
+        caller.require_auth();
+
+        let group = get_group(&env, group_id)?;
+        if is_group_settled(&env, group_id) {
+            return Err(Error::GroupAlreadySettled);
+        }
+
+        let next_id = get_next_expense_id(&env, group_id);
+        if next_id == 0 {
+            return Err(Error::NoExpensesToCancel);
+        }
+
+        let last_id = next_id - 1;
+        let expense = get_expense(&env, group_id, last_id)?;
+        if expense.deleted {
+            return Err(Error::ExpenseDeleted);
+        }
+        if expense.payer != caller && !group.admins.contains(&caller) {
+            return Err(Error::NotExpensePayer);
+        }
+
+        Self::tombstone_expense(&env, group_id, last_id, expense, caller)
+    }
+
+    // ─────────────────────────────────────────────
+    //  TEKRARLAYAN HARCAMALAR
+    // ─────────────────────────────────────────────
+
+    /// Tekrarlayan harcama şablonu ekler; ilk vade `schedule.start`, sonra her `period` saniyede bir.
+    /// Payer imzasıyla oluşturulur: sonraki dönemler payer'ın bu onayıyla, imza
+    /// gerekmeden oluşturulur. Alanlar `add_split_expense` ile aynı kurallarla doğrulanır.
+    pub fn add_recurring(
+        env: Env,
+        group_id: u64,
+        payer: Address,
+        amount: i128,
+        token: Address,
+        split_among: Vec<Address>,
+        split_mode: SplitMode,
+        split_values: Vec<i128>,
+        description: String,
+        category: String,
+        schedule: RecurringSchedule,
+    ) -> Result<u64, Error> {
+        payer.require_auth();
+
+        let group = get_group(&env, group_id)?;
+        if is_group_settled(&env, group_id) {
+            return Err(Error::GroupAlreadySettled);
+        }
+        if schedule.period == 0 || schedule.end.is_some_and(|end| end < schedule.start) {
+            return Err(Error::InvalidSchedule);
+        }
+        let mut templates = get_recurring(&env, group_id);
+        if templates.len() >= MAX_RECURRING_TEMPLATES {
+            return Err(Error::TooManyRecurring);
+        }
+
+        let recurring_id = get_next_recurring_id(&env, group_id);
+        let template = RecurringExpense {
+            id: recurring_id,
+            payer: payer.clone(),
+            amount,
+            token,
+            split_among,
+            split_mode,
+            split_values,
+            description,
+            category,
+            schedule: schedule.clone(),
+            materialized: 0,
+        };
+        Self::validate_new_expense(&env, group_id, &group, &Self::occurrence(&env, &template, &group, schedule.start))?;
+
+        templates.set(recurring_id, template);
+        save_recurring(&env, group_id, &templates);
+        set_next_recurring_id(&env, group_id, recurring_id + 1);
+
+        env.events().publish(
+            (Symbol::new(&env, "recurring_added"), group_id, recurring_id),
+            (payer, amount, schedule),
+        );
+        Ok(recurring_id)
+    }
+
+    /// Şablonu iptal eder; oluşturulmuş harcamalar etkilenmez. Payer veya grup admini.
+    pub fn cancel_recurring(env: Env, group_id: u64, caller: Address, recurring_id: u64) -> Result<(), Error> {
+        caller.require_auth();
+
+        let group = get_group(&env, group_id)?;
+        let mut templates = get_recurring(&env, group_id);
+        let template = templates.get(recurring_id).ok_or(Error::RecurringNotFound)?;
+        if template.payer != caller && !group.admins.contains(&caller) {
+            return Err(Error::Unauthorized);
+        }
+
+        templates.remove(recurring_id);
+        save_recurring(&env, group_id, &templates);
+
+        env.events().publish(
+            (Symbol::new(&env, "recurring_cancelled"), group_id, recurring_id),
+            caller,
+        );
+        Ok(())
+    }
+
+    /// Grubun etkin tekrarlayan harcama şablonları (id sırasıyla).
+    pub fn get_recurring(env: Env, group_id: u64) -> Result<Vec<RecurringExpense>, Error> {
+        get_group(&env, group_id)?;
+        Ok(get_recurring(&env, group_id).values())
+    }
+
+    /// Vadesi gelmiş (`vade <= ledger zamanı`) her dönem için bir harcama oluşturur
+    /// ve oluşturulan harcama sayısını döndürür. İzinsizdir ve idempotenttir:
+    /// her dönem şablondaki sayaçla bir kez oluşturulur. Çağrı başına en fazla
+    /// 20 harcama oluşturulur. Artık geçerli olmayan şablonlar (ör. payer gruptan
+    /// ayrıldı) `recurring_invalid` ile, süresi biten şablonlar `recurring_ended`
+    /// ile kaldırılır.
+    pub fn materialize_recurring(env: Env, group_id: u64) -> Result<u32, Error> {
+        let group = get_group(&env, group_id)?;
+        if is_group_settled(&env, group_id) {
+            return Err(Error::GroupAlreadySettled);
+        }
+
+        let now = env.ledger().timestamp();
+        let mut templates = get_recurring(&env, group_id);
+        let mut created: u32 = 0;
+
+        for (recurring_id, mut template) in templates.iter() {
+            let mut ended = false;
+            loop {
+                let due = Self::next_due(&template);
+                if template.schedule.end.is_some_and(|end| due > end) {
+                    ended = true;
+                    break;
+                }
+                if due > now || created >= MAX_MATERIALIZE_PER_CALL {
+                    break;
+                }
+
+                let expense = Self::occurrence(&env, &template, &group, due);
+                if let Err(e) = Self::validate_new_expense(&env, group_id, &group, &expense) {
+                    env.events().publish(
+                        (Symbol::new(&env, "recurring_invalid"), group_id, recurring_id),
+                        e as u32,
+                    );
+                    ended = true;
+                    break;
+                }
+                let expense_id = Self::record_expense(&env, group_id, expense)?;
+                template.materialized += 1;
+                created += 1;
+
+                env.events().publish(
+                    (Symbol::new(&env, "recurring_materialized"), group_id, recurring_id),
+                    (expense_id, due),
+                );
+            }
+
+            if ended {
+                templates.remove(recurring_id);
+                if template.schedule.end.is_some_and(|end| Self::next_due(&template) > end) {
+                    env.events().publish(
+                        (Symbol::new(&env, "recurring_ended"), group_id, recurring_id),
+                        template.materialized,
+                    );
+                }
+            } else {
+                templates.set(recurring_id, template);
+            }
+        }
+
+        save_recurring(&env, group_id, &templates);
+        Ok(created)
+    }
+
+    /// Şablonun sıradaki döneminin vadesi.
+    fn next_due(template: &RecurringExpense) -> u64 {
+        let schedule = &template.schedule;
+        schedule
+            .start
+            .saturating_add(schedule.period.saturating_mul(template.materialized as u64))
+    }
+
+    /// Şablondan oluşturulacak (henüz kaydedilmemiş) harcama. Kaydeden kontratın
+    /// kendisidir; harcama zamanı dönemin vadesidir.
+    fn occurrence(env: &Env, template: &RecurringExpense, group: &Group, due: u64) -> Expense {
+        let now = Self::ledger_stamp(env);
+        Expense {
+            id: 0,
+            submitter: env.current_contract_address(),
+            contributions: Map::from_array(env, [(template.payer.clone(), template.amount)]),
+            payer: template.payer.clone(),
+            amount: template.amount,
+            token: template.token.clone(),
+            split_among: template.split_among.clone(),
+            description: template.description.clone(),
+            category: template.category.clone(),
+            split_mode: template.split_mode,
+            split_values: template.split_values.clone(),
+            rounding: group.rounding,
+            deleted: false,
+            status: ExpenseStatus::Active,
+            created: now.clone(),
+            modified: now,
+            occurred_at: Some(due),
+        }
+    }
+
+    /// Şu anki ledger zamanı ve sıra numarası.
+    fn ledger_stamp(env: &Env) -> LedgerStamp {
+        LedgerStamp {
+            timestamp: env.ledger().timestamp(),
+            sequence: env.ledger().sequence(),
+        }
+    }
+
+    // ─────────────────────────────────────────────
+    //  HARCAMA ONAYI & İTİRAZ
+    // ─────────────────────────────────────────────
+
+    /// Grubun harcama onay politikasını ayarlar. Sadece grup adminleri.
+    /// Politika sonraki harcamalara (ve güncellemelere) uygulanır; onay
+    /// bekleyen harcamalar onaylandıkça güncel politikaya göre değerlendirilir.
+    pub fn set_approval_policy(env: Env, group_id: u64, caller: Address, policy: ApprovalPolicy) -> Result<(), GroupError> {
+        caller.require_auth();
+        let group = get_group(&env, group_id)?;
+        Self::require_group_admin(&group, &caller)?;
+        if policy.threshold < 0 {
+            return Err(GroupError::InvalidAmount);
+        }
+
+        set_approval_policy(&env, group_id, &Some(policy.clone()));
+        env.events().publish(
+            (Symbol::new(&env, "approval_policy_set"), group_id),
+            (caller, policy),
+        );
+        Ok(())
+    }
+
+    /// Onay politikasını kaldırır; yeni harcamalar doğrudan aktif olur.
+    pub fn clear_approval_policy(env: Env, group_id: u64, caller: Address) -> Result<(), GroupError> {
+        caller.require_auth();
+        let group = get_group(&env, group_id)?;
+        Self::require_group_admin(&group, &caller)?;
+
+        set_approval_policy(&env, group_id, &None);
+        env.events().publish(
+            (Symbol::new(&env, "approval_policy_cleared"), group_id),
+            caller,
+        );
+        Ok(())
+    }
+
+    /// Grubun onay politikasını döndürür.
+    pub fn get_approval_policy(env: Env, group_id: u64) -> Result<Option<ApprovalPolicy>, Error> {
+        get_group(&env, group_id)?;
+        Ok(get_approval_policy(&env, group_id))
+    }
+
+    /// Onay bekleyen bir harcamayı onaylayan üyeler.
+    pub fn get_expense_approvals(env: Env, group_id: u64, expense_id: u64) -> Result<Vec<Address>, Error> {
+        get_expense(&env, group_id, expense_id)?;
+        Ok(get_expense_approvals(&env, group_id, expense_id))
+    }
+
+    /// Etkilenen bir üye (payer dışındaki borçlu) onay bekleyen harcamayı onaylar.
+    /// Politikanın modu sağlandığında harcama aktifleşir ve bakiyelere katılır.
+    pub fn approve_expense(env: Env, group_id: u64, expense_id: u64, member: Address) -> Result<ExpenseStatus, Error> {
+        member.require_auth();
+
+        let group = get_group(&env, group_id)?;
+        if is_group_settled(&env, group_id) {
+            return Err(Error::GroupAlreadySettled);
+        }
+
+        let mut expense = get_expense(&env, group_id, expense_id)?;
+        if expense.deleted {
+            return Err(Error::ExpenseDeleted);
+        }
+        if expense.status != ExpenseStatus::Pending {
+            return Err(Error::ExpenseNotPending);
+        }
+        let affected = Self::affected_members(&env, &expense);
+        if !affected.contains(&member) {
+            return Err(Error::Unauthorized);
+        }
+        let mut approvals = get_expense_approvals(&env, group_id, expense_id);
+        if approvals.contains(&member) {
+            return Err(Error::AlreadyApproved);
+        }
+        approvals.push_back(member.clone());
+
+        env.events().publish(
+            (Symbol::new(&env, "expense_approval"), group_id, expense_id),
+            member,
+        );
+
+        // Politika sonradan kaldırıldıysa tek onay yeterlidir
+        let approved = match get_approval_policy(&env, group_id).map(|p| p.mode) {
+            Some(ApprovalMode::All) => approvals.len() == affected.len(),
+            Some(ApprovalMode::Majority) => approvals.len() * 2 > affected.len(),
+            None => true,
+        };
+        if !approved {
+            save_expense_approvals(&env, group_id, expense_id, &approvals);
+            return Ok(expense.status);
+        }
+
+        Self::require_participants(&group, &expense)?;
+        expense.status = ExpenseStatus::Active;
+        expense.modified = Self::ledger_stamp(&env);
+        save_expense(&env, group_id, expense_id, &expense);
+        save_expense_approvals(&env, group_id, expense_id, &Vec::new(&env));
+        Self::apply_cached(&env, group_id, &expense, 1)?;
+
+        env.events().publish(
+            (Symbol::new(&env, "expense_activated"), group_id, expense_id),
+            approvals,
+        );
+        Ok(expense.status)
+    }
+
+    /// Etkilenen bir üye harcamaya itiraz eder. Aktif harcamanın etkisi
+    /// bakiyelerden çıkarılır, bekleyen onaylar düşer; harcama payer veya bir
+    /// admin `resolve_dispute` çağırana kadar bakiyelere katılmaz.
+    pub fn dispute_expense(env: Env, group_id: u64, expense_id: u64, member: Address) -> Result<(), Error> {
+        member.require_auth();
+
+        get_group(&env, group_id)?;
+        if is_group_settled(&env, group_id) {
+            return Err(Error::GroupAlreadySettled);
+        }
+
+        let mut expense = get_expense(&env, group_id, expense_id)?;
+        if expense.deleted {
+            return Err(Error::ExpenseDeleted);
+        }
+        if !Self::affected_members(&env, &expense).contains(&member) {
+            return Err(Error::Unauthorized);
+        }
+        if expense.status == ExpenseStatus::Disputed {
+            return Err(Error::ExpenseDisputed);
+        }
+
+        Self::apply_cached(&env, group_id, &expense, -1)?;
+        expense.status = ExpenseStatus::Disputed;
+        expense.modified = Self::ledger_stamp(&env);
+        save_expense(&env, group_id, expense_id, &expense);
+        save_expense_approvals(&env, group_id, expense_id, &Vec::new(&env));
+
+        env.events().publish(
+            (Symbol::new(&env, "expense_disputed"), group_id, expense_id),
+            member,
+        );
+        Ok(())
+    }
+
+    /// İtirazı kapatır ve harcamayı güncel onay politikasıyla yeniden değerlendirir:
+    /// onay gerekmiyorsa aktifleşir, gerekiyorsa (ör. onaylanmadan itiraz edilmişse)
+    /// yeniden `Pending` olur; yeni durumu döndürür. Sadece harcamanın payer'ı veya
+    /// bir grup admini; harcamayı düzeltmek için `update_expense`, geri çekmek için
+    /// `delete_expense` kullanılır.
+    pub fn resolve_dispute(env: Env, group_id: u64, expense_id: u64, caller: Address) -> Result<ExpenseStatus, Error> {
+        caller.require_auth();
+
+        let group = get_group(&env, group_id)?;
+        if is_group_settled(&env, group_id) {
+            return Err(Error::GroupAlreadySettled);
+        }
+
+        let mut expense = get_expense(&env, group_id, expense_id)?;
+        if expense.deleted {
+            return Err(Error::ExpenseDeleted);
+        }
+        if expense.payer != caller && !group.admins.contains(&caller) {
+            return Err(Error::Unauthorized);
+        }
+        if expense.status != ExpenseStatus::Disputed {
+            return Err(Error::ExpenseNotDisputed);
+        }
+
+        expense.status = Self::initial_status(&env, group_id, &expense);
+        if expense.status == ExpenseStatus::Active {
+            Self::require_participants(&group, &expense)?;
+        }
+        expense.modified = Self::ledger_stamp(&env);
+        save_expense(&env, group_id, expense_id, &expense);
+        Self::apply_cached(&env, group_id, &expense, 1)?;
+
+        env.events().publish(
+            (Symbol::new(&env, "dispute_resolved"), group_id, expense_id),
+            caller,
+        );
+        if expense.status == ExpenseStatus::Pending {
+            env.events().publish(
+                (Symbol::new(&env, "expense_pending"), group_id, expense_id),
+                Self::affected_members(&env, &expense),
+            );
+        }
+        Ok(expense.status)
+    }
+
+    /// Aktifleşecek harcamanın ödeyenlerinin ve borçlularının hâlâ grupta olduğunu
+    /// doğrular; onay veya itiraz beklerken çıkarılan üyeye bakiye yazılamaz.
+    fn require_participants(group: &Group, expense: &Expense) -> Result<(), Error> {
+        for payer in expense.contributions.keys().iter() {
+            if !group.members.contains(&payer) {
+                return Err(Error::PayerNotMember);
+            }
+        }
+        for member in expense.split_among.iter() {
+            if !group.members.contains(&member) {
+                return Err(Error::SplitMemberNotInGroup);
+            }
+        }
+        Ok(())
+    }
+
+    /// Harcamanın onayını gerektiren üyeler: ödeyenler dışındaki borçlular (tekrarsız).
+    fn affected_members(env: &Env, expense: &Expense) -> Vec<Address> {
+        let mut affected: Vec<Address> = Vec::new(env);
+        for member in expense.split_among.iter() {
+            if !expense.contributions.contains_key(member.clone()) && !affected.contains(&member) {
+                affected.push_back(member);
+            }
+        }
+        affected
+    }
+
+    /// Politikaya göre yeni (veya güncellenen) harcamanın başlangıç durumu:
+    /// tutar eşiği aşıyor ve payer dışında borçlu varsa `Pending`, yoksa `Active`.
+    fn initial_status(env: &Env, group_id: u64, expense: &Expense) -> ExpenseStatus {
+        match get_approval_policy(env, group_id) {
+            Some(policy)
+                if expense.amount > policy.threshold
+                    && !Self::affected_members(env, expense).is_empty() =>
+            {
+                ExpenseStatus::Pending
+            }
+            _ => ExpenseStatus::Active,
+        }
+    }
+
+    // ─────────────────────────────────────────────
+    //  ÜYE EKLEME / ÇIKARMA (sadece settle öncesi)
+    // ─────────────────────────────────────────────
+
+    /// Gruba yeni üye davet eder. Sadece grup adminleri çağırabilir; grup settle edilmemiş olmalı.
+    /// Davet edilen kişi `accept_invite` ile kabul edene kadar üye sayılmaz; bekleyen
+    /// bir daveti yeniden göndermek süresini yeniler.
+    pub fn add_member(env: Env, group_id: u64, caller: Address, new_member: Address) -> Result<(), GroupError> {
+        caller.require_auth();
+
+        if is_group_settled(&env, group_id) {
+            return Err(GroupError::GroupAlreadySettled);
+        }
+
+        let group = get_group(&env, group_id)?;
+        Self::require_group_admin(&group, &caller)?;
+
+        if group.members.contains(&new_member) {
+            return Err(GroupError::AlreadyAMember);
+        }
+
+        // Bekleyen davetler de yer tutar; mevcut daveti yenilemek sayıyı artırmaz
+        let mut invites = get_invites(&env, group_id);
+        if !invites.contains_key(new_member.clone()) && group.members.len() + invites.len() >= MAX_MEMBERS {
+            return Err(GroupError::TooManyMembers);
+        }
+        Self::send_invite(&env, group_id, &mut invites, &caller, &new_member);
+        save_invites(&env, group_id, &invites);
+
+        Ok(())
+    }
+
+    /// Davet edilen kişi daveti kendi imzasıyla kabul eder ve üye olur.
+    pub fn accept_invite(env: Env, group_id: u64, invitee: Address) -> Result<(), GroupError> {
+        invitee.require_auth();
+
+        if is_group_settled(&env, group_id) {
+            return Err(GroupError::GroupAlreadySettled);
+        }
+
+        let mut group = get_group(&env, group_id)?;
+        let mut invites = get_invites(&env, group_id);
+        let invite = invites.get(invitee.clone()).ok_or(GroupError::InviteNotFound)?;
+        if env.ledger().timestamp() > invite.expires_at {
+            return Err(GroupError::InviteExpired);
+        }
+
+        invites.remove(invitee.clone());
+        save_invites(&env, group_id, &invites);
+        group.members.push_back(invitee.clone());
+        save_group(&env, group_id, &group);
+
+        env.events().publish(
+            (Symbol::new(&env, "invite_accepted"), group_id),
+            invitee.clone(),
+        );
+        env.events().publish(
+            (Symbol::new(&env, "member_added"), group_id),
+            invitee,
+        );
+
+        Ok(())
+    }
+
+    /// Daveti reddeder. Davet edilen kişi veya bir grup admini (geri çekme) çağırabilir.
+    pub fn decline_invite(env: Env, group_id: u64, caller: Address, invitee: Address) -> Result<(), GroupError> {
+        caller.require_auth();
+
+        let group = get_group(&env, group_id)?;
+        if caller != invitee {
+            Self::require_group_admin(&group, &caller)?;
+        }
+
+        let mut invites = get_invites(&env, group_id);
+        if invites.remove(invitee.clone()).is_none() {
+            return Err(GroupError::InviteNotFound);
+        }
+        save_invites(&env, group_id, &invites);
+
+        env.events().publish(
+            (Symbol::new(&env, "invite_declined"), group_id, invitee),
+            caller,
+        );
+
+        Ok(())
+    }
+
+    /// Süresi dolmuş davetleri temizler ve temizlenen sayıyı döndürür. İzinsizdir.
+    pub fn expire_invites(env: Env, group_id: u64) -> Result<u32, GroupError> {
+        get_group(&env, group_id)?;
+        let now = env.ledger().timestamp();
+        let mut invites = get_invites(&env, group_id);
+        let mut expired = 0;
+        for (invitee, invite) in invites.clone().iter() {
+            if now > invite.expires_at {
+                invites.remove(invitee.clone());
+                expired += 1;
+                env.events().publish(
+                    (Symbol::new(&env, "invite_expired"), group_id),
+                    invitee,
+                );
+            }
+        }
+        save_invites(&env, group_id, &invites);
+        Ok(expired)
+    }
+
+    /// Grubun bekleyen (süresi dolmuş olanlar dahil) davetlerini döndürür.
+    pub fn get_invites(env: Env, group_id: u64) -> Result<Vec<Invite>, Error> {
+        get_group(&env, group_id)?;
+        Ok(get_invites(&env, group_id).values())
+    }
+
+    /// `invites` haritasına yeni bir davet yazar ve `invite_sent` yayınlar; kaydetmek çağırana aittir.
+    fn send_invite(env: &Env, group_id: u64, invites: &mut Map<Address, Invite>, inviter: &Address, invitee: &Address) {
+        let expires_at = env.ledger().timestamp() + INVITE_TTL_SECONDS;
+        invites.set(
+            invitee.clone(),
+            Invite {
+                invitee: invitee.clone(),
+                inviter: inviter.clone(),
+                expires_at,
+            },
+        );
+
+        env.events().publish(
+            (Symbol::new(env, "invite_sent"), group_id, invitee.clone()),
+            (inviter.clone(), expires_at),
+        );
+    }
+
+    /// Gruptan üye çıkarır. Üye kendini, admin sıradan üyeleri, owner adminleri çıkarabilir;
+    /// owner çıkarılamaz ve en az 2 üye kalmalı. Herhangi bir token'da net bakiyesi
+    /// sıfır olmayan veya onay bekleyen/itirazlı bir harcamada payı olan üye
+    /// çıkarılamaz (`MemberHasBalance`).
+    pub fn remove_member(env: Env, group_id: u64, caller: Address, member_to_remove: Address) -> Result<(), GroupError> {
+        caller.require_auth();
+
+        if is_group_settled(&env, group_id) {
+            return Err(GroupError::GroupAlreadySettled);
+        }
+
+        let mut group = get_group(&env, group_id)?;
+        // Üye kendini çıkarabilir; başkasını çıkarmak admin, admini çıkarmak owner ister
+        if caller != member_to_remove {
+            Self::require_group_admin(&group, &caller)?;
+            if group.admins.contains(&member_to_remove) && caller != group.owner {
+                return Err(GroupError::Unauthorized);
+            }
+        } else if !group.members.contains(&caller) {
+            return Err(GroupError::NotAMember);
+        }
+        // Owner çıkarılamaz; önce sahipliği devretmeli
+        if member_to_remove == group.owner {
+            return Err(GroupError::Unauthorized);
+        }
+        // Açık borcu/alacağı olan üye çıkarılamaz; borçlu `leave_group` kullanabilir
+        if Self::has_frozen_share(&env, group_id, &member_to_remove)? {
+            return Err(GroupError::MemberHasBalance);
+        }
+        for token in group.tokens.iter() {
+            let balances = Self::token_balances(&env, group_id, &token)?;
+            if balances.get(member_to_remove.clone()).unwrap_or(0) != 0 {
+                return Err(GroupError::MemberHasBalance);
+            }
+        }
+
+        Self::detach_member(&env, group_id, &mut group, &member_to_remove)
+    }
+
+    /// Üye gruptan ayrılır: her token'daki borcunu en büyük alacaklılardan
+    /// başlayarak kapatır (ödemeler `record_payment` gibi kaydedilir), sonra
+    /// üyelikten çıkar. Alacaklı üye, önce borçluların ödemesini beklemelidir
+    /// (`MemberHasBalance`); onay bekleyen veya itirazlı bir harcamada payı olan üye de
+    /// harcama sonuçlanana kadar ayrılamaz. Owner önce sahipliği devretmelidir.
+    pub fn leave_group(env: Env, group_id: u64, member: Address) -> Result<Vec<Settlement>, GroupError> {
+        member.require_auth();
+
+        if is_group_settled(&env, group_id) {
+            return Err(GroupError::GroupAlreadySettled);
+        }
+
+        let mut group = get_group(&env, group_id)?;
+        if !group.members.contains(&member) {
+            return Err(GroupError::NotAMember);
+        }
+        if member == group.owner {
+            return Err(GroupError::Unauthorized);
+        }
+        if group.members.len() <= MIN_MEMBERS {
+            return Err(GroupError::NotEnoughMembers);
+        }
+        // Askıdaki harcama bakiyelere yansımadığından çıkış borcunda görünmez
+        if Self::has_frozen_share(&env, group_id, &member)? {
+            return Err(GroupError::MemberHasBalance);
+        }
+
+        let mut transfers: Vec<Settlement> = Vec::new(&env);
+        for token in group.tokens.iter() {
+            let mut balances = Self::token_balances(&env, group_id, &token)?;
+            if balances.get(member.clone()).unwrap_or(0) > 0 {
+                return Err(GroupError::MemberHasBalance);
+            }
+            let exits = compute_exit_settlements(&env, &token, &balances, &member);
+            for s in exits.iter() {
+                Self::execute_payment(&env, group_id, &s.from, &s.to, &s.token, s.amount, &mut balances);
+                transfers.push_back(s);
+            }
+            save_group_balances(&env, group_id, &token, &balances);
+        }
+
+        Self::detach_member(&env, group_id, &mut group, &member)?;
+
+        env.events().publish(
+            (Symbol::new(&env, "member_left"), group_id),
+            (member, transfers.len()),
+        );
+
+        Ok(transfers)
+    }
+
+    /// Üye, onay bekleyen veya itirazlı bir harcamada ödeyen ya da bölüşen mi?
+    /// Bu harcamalar bakiyelere aktif olunca yansır; üye o zamana kadar ayrılamaz.
+    fn has_frozen_share(env: &Env, group_id: u64, member: &Address) -> Result<bool, Error> {
+        for id in get_frozen_expenses(env, group_id).iter() {
+            let expense = get_expense(env, group_id, id)?;
+            if expense.contributions.contains_key(member.clone()) || expense.split_among.contains(member) {
+                return Ok(true);
+            }
+        }
+        Ok(false)
+    }
+
+    /// Üyeyi üye ve admin listelerinden çıkarır ve grubu kaydeder.
+    fn detach_member(env: &Env, group_id: u64, group: &mut Group, member: &Address) -> Result<(), GroupError> {
+        if group.members.len() <= MIN_MEMBERS {
+            return Err(GroupError::NotEnoughMembers);
+        }
+        let idx = group.members.first_index_of(member).ok_or(GroupError::NotAMember)?;
+        group.members.remove(idx);
+        if let Some(idx) = group.admins.first_index_of(member) {
+            group.admins.remove(idx);
+        }
+        save_group(env, group_id, group);
+
+        env.events().publish(
+            (Symbol::new(env, "member_removed"), group_id),
+            member.clone(),
+        );
+
+        Ok(())
+    }
+
+    /// Bir üyeye grup admini yetkisi verir (`is_admin = true`) veya geri alır.
+    /// Sadece grup sahibi (owner) çağırabilir; owner'ın adminliği geri alınamaz (`LastAdmin`).
+    pub fn set_group_admin(env: Env, group_id: u64, caller: Address, member: Address, is_admin: bool) -> Result<(), GroupError> {
+        caller.require_auth();
+
+        let mut group = get_group(&env, group_id)?;
+        Self::require_group_owner(&group, &caller)?;
+        if !group.members.contains(&member) {
+            return Err(GroupError::NotAMember);
+        }
+
+        match group.admins.first_index_of(&member) {
+            Some(idx) if !is_admin => {
+                // Owner her zaman admindir
+                if member == group.owner {
+                    return Err(GroupError::LastAdmin);
+                }
+                group.admins.remove(idx);
+            }
+            None if is_admin => group.admins.push_back(member.clone()),
+            _ => {}
+        }
         save_group(&env, group_id, &group);
-        set_next_group_id(&env, group_id + 1);
-        set_expenses_count(&env, group_id, 0);
 
-        // ── Event Yayınla ──
         env.events().publish(
-            (Symbol::new(&env, "group_created"), group_id),
-            creator,
+            (Symbol::new(&env, "admin_set"), group_id, member),
+            is_admin,
         );
 
-        group_id
+        Ok(())
     }
 
-    // ─────────────────────────────────────────────
-    //  HARCAMA EKLEME
-    // ─────────────────────────────────────────────
+    /// Grup sahipliğini başka bir üyeye devreder; yeni owner admin yapılır,
+    /// eski owner admin olarak kalır. Sadece owner.
+    pub fn transfer_group_ownership(env: Env, group_id: u64, caller: Address, new_owner: Address) -> Result<(), GroupError> {
+        caller.require_auth();
 
-    /// Gruba yeni bir harcama ekler.
-    /// Validasyonlar: pozitif tutar, payer grupta, split_among grupta, boş olmayan split.
-    pub fn add_expense(
-        env: Env,
-        group_id: u64,
-        payer: Address,
-        amount: i128,
-        split_among: Vec<Address>,
-        description: String,
-        category: String,
-    ) -> u64 {
-        payer.require_auth();
+        let mut group = get_group(&env, group_id)?;
+        Self::require_group_owner(&group, &caller)?;
+        if !group.members.contains(&new_owner) {
+            return Err(GroupError::NotAMember);
+        }
+        if !group.admins.contains(&new_owner) {
+            group.admins.push_back(new_owner.clone());
+        }
+        group.owner = new_owner.clone();
+        save_group(&env, group_id, &group);
 
-        // Grup var mı + settled kontrolü
-        let group = get_group(&env, group_id);
+        env.events().publish(
+            (Symbol::new(&env, "owner_changed"), group_id, caller),
+            new_owner,
+        );
 
-        if is_group_settled(&env, group_id) {
-            panic!("group is already settled");
+        Ok(())
+    }
+
+    /// Üyenin gruptaki rolünü döndürür (üye değilse `Error::NotAMember`).
+    pub fn get_member_role(env: Env, group_id: u64, member: Address) -> Result<GroupRole, Error> {
+        let group = get_group(&env, group_id)?;
+        if !group.members.contains(&member) {
+            return Err(Error::NotAMember);
+        }
+        if member == group.owner {
+            Ok(GroupRole::Owner)
+        } else if group.admins.contains(&member) {
+            Ok(GroupRole::Admin)
+        } else {
+            Ok(GroupRole::Member)
         }
+    }
 
-        // Payer grupta mı
-        let mut payer_in_group = false;
-        for i in 0..group.members.len() {
-            if group.members.get(i).unwrap() == payer {
-                payer_in_group = true;
-                break;
-            }
+    /// Çağıranın grup admini (owner dahil) olduğunu doğrular.
+    fn require_group_admin(group: &Group, caller: &Address) -> Result<(), Error> {
+        if !group.members.contains(caller) {
+            return Err(Error::NotAMember);
         }
-        if !payer_in_group {
-            panic!("payer is not a member of the group");
+        if !group.admins.contains(caller) {
+            return Err(Error::NotGroupAdmin);
         }
+        Ok(())
+    }
 
-        // Amount pozitif olmalı
-        if amount <= 0 {
-            panic!("amount must be positive");
+    /// Çağıranın grup sahibi olduğunu doğrular.
+    fn require_group_owner(group: &Group, caller: &Address) -> Result<(), Error> {
+        Self::require_group_admin(group, caller)?;
+        if *caller != group.owner {
+            return Err(Error::Unauthorized);
         }
+        Ok(())
+    }
 
-        // split_among boş olmamalı
-        if split_among.is_empty() {
-            panic!("split_among cannot be empty");
+    /// Grubun harcama token izin listesine bir token ekler (`allowed = true`) veya çıkarır.
+    /// Sadece adminler çağırabilir. Ana token (`group.token`) çıkarılamaz; açık bakiyesi
+    /// olan bir token da tüm bakiyeler sıfırlanana kadar çıkarılamaz.
+    pub fn set_group_token(env: Env, group_id: u64, caller: Address, token: Address, allowed: bool) -> Result<(), GroupError> {
+        caller.require_auth();
+
+        if is_group_settled(&env, group_id) {
+            return Err(GroupError::GroupAlreadySettled);
         }
 
-        // split_among içindeki herkes grupta olmalı
-        for i in 0..split_among.len() {
-            let addr = split_among.get(i).unwrap();
-            let mut found = false;
-            for j in 0..group.members.len() {
-                if group.members.get(j).unwrap() == addr {
-                    found = true;
-                    break;
+        let mut group = get_group(&env, group_id)?;
+        if !group.admins.contains(&caller) {
+            return Err(GroupError::NotGroupAdmin);
+        }
+
+        match group.tokens.first_index_of(&token) {
+            Some(idx) if !allowed => {
+                if token == group.token {
+                    return Err(GroupError::PrimaryTokenRequired);
                 }
+                let balances = Self::token_balances(&env, group_id, &token)?;
+                for (_, balance) in balances.iter() {
+                    if balance != 0 {
+                        return Err(GroupError::TokenHasBalances);
+                    }
+                }
+                group.tokens.remove(idx);
             }
-            if !found {
-                panic!("split_among contains non-member");
+            None if allowed => {
+                group.tokens.push_back(token.clone());
+                save_group_balances(&env, group_id, &token, &Map::new(&env));
             }
+            _ => {}
         }
-
-        let expense_id = get_next_expense_id(&env, group_id);
-        let expense = Expense {
-            id: expense_id,
-            payer: payer.clone(),
-            amount,
-            split_among,
-            description,
-            category,
-        };
-
-        save_expense(&env, group_id, expense_id, &expense);
-        set_next_expense_id(&env, group_id, expense_id + 1);
-
-        // Expense count güncelle (storage + Group struct böylece get_group doğru sayıyı döner)
-        let count = get_expenses_count(&env, group_id);
-        set_expenses_count(&env, group_id, count + 1);
-        let mut group = get_group(&env, group_id);
-        group.expense_count = get_next_expense_id(&env, group_id);
         save_group(&env, group_id, &group);
 
-        // ── Event Yayınla ──
         env.events().publish(
-            (Symbol::new(&env, "expense_added"), group_id, expense_id),
-            (payer, amount),
+            (Symbol::new(&env, "token_set"), group_id, token),
+            allowed,
         );
 
-        expense_id
+        Ok(())
     }
 
-    // ─────────────────────────────────────────────
-    //  SON HARCAMAYI İPTAL (sadece ödeyen, settle öncesi)
-    // ─────────────────────────────────────────────
-
-    /// Son eklenen harcamayı iptal eder. Sadece o harcamayı ekleyen (payer) çağırabilir; grup settle edilmemiş olmalı.
-    pub fn cancel_last_expense(env: Env, group_id: u64, caller: Address) {
+    /// Grubun yuvarlama politikasını değiştirir. Sadece grup adminleri çağırabilir.
+    /// Yalnızca bundan sonra eklenen harcamaları etkiler; mevcut harcamalar
+    /// eklendikleri andaki politikayı korur.
+    pub fn set_rounding_policy(env: Env, group_id: u64, caller: Address, policy: RoundingPolicy) -> Result<(), GroupError> {
         caller.require_auth();
 
         if is_group_settled(&env, group_id) {
-            panic!("group is already settled");
-        }
-
-        let next_id = get_next_expense_id(&env, group_id);
-        if next_id == 0 {
-            panic!("no expenses to cancel");
-        }
-
-        let last_id = next_id - 1;
-        let expense = get_expense(&env, group_id, last_id);
-        if expense.payer != caller {
-            panic!("only the payer can cancel this expense");
+            return Err(GroupError::GroupAlreadySettled);
         }
 
-        remove_expense(&env, group_id, last_id);
-        set_next_expense_id(&env, group_id, last_id);
+        let mut group = get_group(&env, group_id)?;
+        Self::require_group_admin(&group, &caller)?;
 
-        let count = get_expenses_count(&env, group_id);
-        set_expenses_count(&env, group_id, count - 1);
-        let mut group = get_group(&env, group_id);
-        group.expense_count = get_next_expense_id(&env, group_id);
+        group.rounding = policy;
         save_group(&env, group_id, &group);
 
         env.events().publish(
-            (Symbol::new(&env, "expense_cancelled"), group_id, last_id),
-            caller,
+            (Symbol::new(&env, "rounding_set"), group_id),
+            policy as u32,
         );
+
+        Ok(())
     }
 
     // ─────────────────────────────────────────────
-    //  ÜYE EKLEME / ÇIKARMA (sadece settle öncesi)
+    //  KATEGORİ KAYDI (sadece grup adminleri)
     // ─────────────────────────────────────────────
 
-    /// Gruba yeni üye ekler. Sadece mevcut üyeler çağırabilir; grup settle edilmemiş olmalı.
-    pub fn add_member(env: Env, group_id: u64, caller: Address, new_member: Address) {
+    /// Gruba yeni bir kategori ekler. `id` harcamalarda saklanan kalıcı anahtardır,
+    /// `name` görünen addır (ikisi de en fazla 32 bayt, uzunsa `CategoryTooLong`). Boş id kategorisiz
+    /// harcamalar için ayrılmıştır.
+    /// Kayıt varsayılanlar dahil en fazla 32 kategori tutar.
+    pub fn add_category(env: Env, group_id: u64, caller: Address, id: String, name: String) -> Result<(), GroupError> {
         caller.require_auth();
-
-        if is_group_settled(&env, group_id) {
-            panic!("group is already settled");
+        let group = get_group(&env, group_id)?;
+        Self::require_group_admin(&group, &caller)?;
+        if id.is_empty() || name.is_empty() {
+            return Err(GroupError::InvalidCategory);
+        }
+        if id.len() > MAX_CATEGORY_LEN || name.len() > MAX_CATEGORY_LEN {
+            return Err(GroupError::CategoryTooLong);
         }
 
-        let mut group = get_group(&env, group_id);
-        let mut caller_in_group = false;
-        for i in 0..group.members.len() {
-            if group.members.get(i).unwrap() == caller {
-                caller_in_group = true;
-                break;
-            }
+        let mut categories = Self::group_categories(&env, group_id);
+        if categories.contains_key(id.clone()) {
+            return Err(GroupError::CategoryExists);
         }
-        if !caller_in_group {
-            panic!("only a member can add someone");
+        if categories.len() >= MAX_CATEGORIES {
+            return Err(GroupError::TooManyCategories);
         }
+        categories.set(id.clone(), name.clone());
+        save_categories(&env, group_id, &categories);
 
-        for i in 0..group.members.len() {
-            if group.members.get(i).unwrap() == new_member {
-                panic!("address is already a member");
-            }
+        env.events().publish(
+            (Symbol::new(&env, "category_added"), group_id),
+            (id, name),
+        );
+        Ok(())
+    }
+
+    /// Kategorinin görünen adını değiştirir. Harcamalar id'yi sakladığı için
+    /// hiçbir harcama yeniden yazılmaz; özetler ve filtreler aynı id ile çalışır.
+    pub fn rename_category(env: Env, group_id: u64, caller: Address, id: String, name: String) -> Result<(), GroupError> {
+        caller.require_auth();
+        let group = get_group(&env, group_id)?;
+        Self::require_group_admin(&group, &caller)?;
+        if name.is_empty() {
+            return Err(GroupError::InvalidCategory);
+        }
+        // Uzun id kayda bakılmadan reddedilir (bkz. `require_category`)
+        if id.len() > MAX_CATEGORY_LEN || name.len() > MAX_CATEGORY_LEN {
+            return Err(GroupError::CategoryTooLong);
         }
 
-        group.members.push_back(new_member.clone());
-        save_group(&env, group_id, &group);
+        let mut categories = Self::group_categories(&env, group_id);
+        let old = categories.get(id.clone()).ok_or(GroupError::CategoryNotFound)?;
+        categories.set(id.clone(), name.clone());
+        save_categories(&env, group_id, &categories);
 
         env.events().publish(
-            (Symbol::new(&env, "member_added"), group_id),
-            new_member,
+            (Symbol::new(&env, "category_renamed"), group_id, id),
+            (old, name),
         );
+        Ok(())
     }
 
-    /// Gruptan üye çıkarır. Sadece mevcut üyeler çağırabilir; en az 2 üye kalmalı.
-    pub fn remove_member(env: Env, group_id: u64, caller: Address, member_to_remove: Address) {
+    /// Kategoriyi kayıttan çıkarır; yeni harcamalarda kullanılamaz. Mevcut
+    /// harcamalar id'yi korur ve özetlerde görünmeye devam eder.
+    pub fn remove_category(env: Env, group_id: u64, caller: Address, id: String) -> Result<(), GroupError> {
         caller.require_auth();
+        let group = get_group(&env, group_id)?;
+        Self::require_group_admin(&group, &caller)?;
 
-        if is_group_settled(&env, group_id) {
-            panic!("group is already settled");
+        let mut categories = Self::group_categories(&env, group_id);
+        if categories.remove(id.clone()).is_none() {
+            return Err(GroupError::CategoryNotFound);
         }
+        save_categories(&env, group_id, &categories);
 
-        let mut group = get_group(&env, group_id);
-        let mut caller_in_group = false;
-        for i in 0..group.members.len() {
-            if group.members.get(i).unwrap() == caller {
-                caller_in_group = true;
-                break;
+        env.events().publish(
+            (Symbol::new(&env, "category_removed"), group_id),
+            id,
+        );
+        Ok(())
+    }
+
+    /// Grubun kategori kaydı: id -> görünen ad.
+    pub fn get_categories(env: Env, group_id: u64) -> Result<Map<String, String>, Error> {
+        get_group(&env, group_id)?;
+        Ok(Self::group_categories(&env, group_id))
+    }
+
+    /// Kayıtlı kategoriler; grup kaydı hiç düzenlenmemişse varsayılanlar.
+    fn group_categories(env: &Env, group_id: u64) -> Map<String, String> {
+        get_categories(env, group_id).unwrap_or_else(|| {
+            let mut defaults = Map::new(env);
+            for (id, name) in DEFAULT_CATEGORIES {
+                defaults.set(String::from_str(env, id), String::from_str(env, name));
             }
+            defaults
+        })
+    }
+
+    /// Harcama kategorisinin kayıtlı (veya boş = kategorisiz) olduğunu doğrular.
+    /// Kayıttaki hiçbir id 32 bayttan uzun olamayacağı için uzunluk kayda bakılmadan reddedilir.
+    fn require_category(env: &Env, group_id: u64, category: &String) -> Result<(), Error> {
+        if category.len() > MAX_CATEGORY_LEN {
+            return Err(Error::CategoryTooLong);
         }
-        if !caller_in_group {
-            panic!("only a member can remove someone");
+        if category.is_empty() || Self::group_categories(env, group_id).contains_key(category.clone()) {
+            Ok(())
+        } else {
+            Err(Error::CategoryNotFound)
         }
-        if group.members.len() <= MIN_MEMBERS {
-            panic!("cannot remove: at least 2 members required");
+    }
+
+    // ─────────────────────────────────────────────
+    //  BAKİYE HESAPLAMA
+    // ─────────────────────────────────────────────
+
+    /// Gruptaki herkesin grubun ana token'ındaki (`group.token`) net bakiyesini döndürür.
+    /// Pozitif = alacaklı, Negatif = borçlu
+    ///
+    /// Bakiyeler harcama yazımlarında artımlı güncellenen önbellekten okunur;
+    /// önbellek yoksa (eski gruplar) harcama kaydı baştan oynatılır.
+    pub fn get_balances(env: Env, group_id: u64) -> Result<Map<Address, i128>, Error> {
+        let group = get_group(&env, group_id)?;
+        Self::token_balances(&env, group_id, &group.token)
+    }
+
+    /// Belirli bir token cinsinden net bakiyeleri döndürür.
+    pub fn get_token_balances(env: Env, group_id: u64, token: Address) -> Result<Map<Address, i128>, Error> {
+        let group = get_group(&env, group_id)?;
+        if !group.tokens.contains(&token) {
+            return Err(Error::TokenNotAllowed);
         }
+        Self::token_balances(&env, group_id, &token)
+    }
 
-        let mut new_members = Vec::new(&env);
-        for i in 0..group.members.len() {
-            let m = group.members.get(i).unwrap();
-            if m != member_to_remove {
-                new_members.push_back(m);
+    /// İzinli tüm token'lar için net bakiyeleri döndürür: token -> (üye -> bakiye).
+    pub fn get_all_balances(env: Env, group_id: u64) -> Result<Map<Address, Map<Address, i128>>, Error> {
+        let group = get_group(&env, group_id)?;
+        let mut all = Map::new(&env);
+        for token in group.tokens.iter() {
+            let balances = Self::token_balances(&env, group_id, &token)?;
+            all.set(token, balances);
+        }
+        Ok(all)
+    }
+
+    /// Üye bazında toplamlar: ödenen (`paid`) ve payına düşen (`owed`) tutar.
+    /// Sadece aktif harcamalar sayılır; geri ödemeler bu toplamları etkilemez.
+    pub fn get_member_totals(env: Env, group_id: u64, token: Address) -> Result<Map<Address, MemberTotals>, Error> {
+        Ok(Self::token_summary(&env, group_id, &token)?.members)
+    }
+
+    /// Kategori bazında toplam harcama ve her kategoride üye toplamları.
+    pub fn get_category_summary(env: Env, group_id: u64, token: Address) -> Result<Map<String, CategoryTotals>, Error> {
+        Ok(Self::token_summary(&env, group_id, &token)?.categories)
+    }
+
+    /// Önbelleği harcama kaydıyla karşılaştırır (denetim için, salt okunur).
+    /// `true` = her token için bakiye ve harcama özeti önbelleği, tüm harcamaların
+    /// baştan oynatılmasıyla aynı.
+    pub fn verify_balances(env: Env, group_id: u64) -> Result<bool, Error> {
+        let group = get_group(&env, group_id)?;
+        let replayed = Self::replay_balances(&env, group_id)?;
+        let summaries = Self::replay_summaries(&env, group_id)?;
+        for token in group.tokens.iter() {
+            let expected = replayed.get(token.clone()).unwrap_or(Map::new(&env));
+            match get_group_balances(&env, group_id, &token) {
+                Some(cached) if balances_match(&cached, &expected) => {}
+                _ => return Ok(false),
+            }
+            let expected = summaries.get(token.clone()).unwrap_or(empty_summary(&env));
+            if get_expense_summary(&env, group_id, &token).unwrap_or(empty_summary(&env)) != expected {
+                return Ok(false);
             }
         }
-        if new_members.len() == group.members.len() {
-            panic!("address is not a member");
+        Ok(true)
+    }
+
+    /// Net bakiyeleri (ve harcama özetini) harcama kaydından yeniden hesaplayıp
+    /// önbelleğe yazar. Sonuç yalnızca zincir üstü veriden türetildiği için herkes çağırabilir.
+    pub fn recompute_balances(env: Env, group_id: u64) -> Result<Map<Address, Map<Address, i128>>, Error> {
+        let group = get_group(&env, group_id)?;
+        let replayed = Self::replay_balances(&env, group_id)?;
+        let summaries = Self::replay_summaries(&env, group_id)?;
+        let mut all = Map::new(&env);
+        for token in group.tokens.iter() {
+            let balances = replayed.get(token.clone()).unwrap_or(Map::new(&env));
+            ensure_zero_sum(&balances)?;
+            save_group_balances(&env, group_id, &token, &balances);
+            let summary = summaries.get(token.clone()).unwrap_or(empty_summary(&env));
+            save_expense_summary(&env, group_id, &token, &summary);
+            all.set(token, balances);
         }
-        group.members = new_members;
-        save_group(&env, group_id, &group);
 
         env.events().publish(
-            (Symbol::new(&env, "member_removed"), group_id),
-            member_to_remove,
+            (Symbol::new(&env, "balances_recomputed"), group_id),
+            all.len(),
         );
+
+        Ok(all)
     }
 
-    // ─────────────────────────────────────────────
-    //  BAKİYE HESAPLAMA
-    // ─────────────────────────────────────────────
+    /// Tek bir token'ın net bakiyeleri: önbellekten, yoksa kayıttan.
+    fn token_balances(env: &Env, group_id: u64, token: &Address) -> Result<Map<Address, i128>, Error> {
+        let balances = match get_group_balances(env, group_id, token) {
+            Some(b) => b,
+            None => Self::replay_balances(env, group_id)?
+                .get(token.clone())
+                .unwrap_or(Map::new(env)),
+        };
 
-    /// Gruptaki herkesin net bakiyesini hesaplar.
-    /// Pozitif = alacaklı, Negatif = borçlu
-    pub fn get_balances(env: Env, group_id: u64) -> Map<Address, i128> {
-        let _group = get_group(&env, group_id);
-        let expense_count = get_next_expense_id(&env, group_id);
-        let mut balances: Map<Address, i128> = Map::new(&env);
+        // Değişmez: net bakiyeler her zaman tam sıfıra toplanır
+        ensure_zero_sum(&balances)?;
+        Ok(balances)
+    }
+
+    /// Bir harcamanın etkisini kendi token'ının bakiye önbelleğine uygular.
+    /// Aktif olmayan (onay bekleyen / itirazlı) harcamalar bakiyelere katılmaz.
+    fn apply_cached(env: &Env, group_id: u64, expense: &Expense, sign: i128) -> Result<(), Error> {
+        if expense.status != ExpenseStatus::Active {
+            return Ok(());
+        }
+        let mut balances = Self::token_balances(env, group_id, &expense.token)?;
+        apply_expense(env, &mut balances, expense, sign)?;
+        save_group_balances(env, group_id, &expense.token, &balances);
+
+        let mut summary = get_expense_summary(env, group_id, &expense.token).unwrap_or(empty_summary(env));
+        apply_summary(env, &mut summary, expense, sign)?;
+        save_expense_summary(env, group_id, &expense.token, &summary);
+        Ok(())
+    }
+
+    /// Tek bir token'ın harcama özeti (token grubun izin listesinde olmalı).
+    fn token_summary(env: &Env, group_id: u64, token: &Address) -> Result<ExpenseSummary, Error> {
+        let group = get_group(env, group_id)?;
+        if !group.tokens.contains(token) {
+            return Err(Error::TokenNotAllowed);
+        }
+        Ok(get_expense_summary(env, group_id, token).unwrap_or(empty_summary(env)))
+    }
+
+    /// Aktif harcamaları baştan oynatarak token bazında harcama özetini hesaplar.
+    fn replay_summaries(env: &Env, group_id: u64) -> Result<Map<Address, ExpenseSummary>, Error> {
+        let mut all: Map<Address, ExpenseSummary> = Map::new(env);
+        for eid in 0..get_next_expense_id(env, group_id) {
+            let expense = get_expense(env, group_id, eid)?;
+            if expense.deleted || expense.status != ExpenseStatus::Active {
+                continue;
+            }
+            let mut summary = all.get(expense.token.clone()).unwrap_or(empty_summary(env));
+            apply_summary(env, &mut summary, &expense, 1)?;
+            all.set(expense.token.clone(), summary);
+        }
+        Ok(all)
+    }
+
+    /// Tüm harcama ve ödemeleri baştan oynatarak token bazında net bakiyeleri hesaplar.
+    fn replay_balances(env: &Env, group_id: u64) -> Result<Map<Address, Map<Address, i128>>, Error> {
+        let expense_count = get_next_expense_id(env, group_id);
+        let mut all: Map<Address, Map<Address, i128>> = Map::new(env);
 
         for eid in 0..expense_count {
-            let expense = get_expense(&env, group_id, eid);
-            let share = expense.amount / (expense.split_among.len() as i128);
-
-            // Payer alacaklı olur (ödediği miktar - kendi payı)
-            let payer_current = balances.get(expense.payer.clone()).unwrap_or(0);
-            balances.set(expense.payer.clone(), payer_current + expense.amount - share);
-
-            // Her split üyesi borçlanır
-            for j in 0..expense.split_among.len() {
-                let member = expense.split_among.get(j).unwrap();
-                if member != expense.payer {
-                    let current = balances.get(member.clone()).unwrap_or(0);
-                    balances.set(member, current - share);
-                }
+            let expense = get_expense(env, group_id, eid)?;
+            if expense.deleted || expense.status != ExpenseStatus::Active {
+                continue;
             }
+            let mut balances = all.get(expense.token.clone()).unwrap_or(Map::new(env));
+            apply_expense(env, &mut balances, &expense, 1)?;
+            all.set(expense.token.clone(), balances);
+        }
+
+        let payment_count = get_next_payment_id(env, group_id);
+        for pid in 0..payment_count {
+            let payment = get_payment(env, group_id, pid)?;
+            let mut balances = all.get(payment.token.clone()).unwrap_or(Map::new(env));
+            apply_payment(&mut balances, &payment);
+            all.set(payment.token.clone(), balances);
         }
 
-        balances
+        Ok(all)
     }
 
     // ─────────────────────────────────────────────
@@ -343,10 +2062,131 @@ impl StellarSplitContract {
     // ─────────────────────────────────────────────
 
     /// Optimal uzlaşma planını hesaplar.
-    /// Greedy min-transaction algoritması kullanır.
-    pub fn compute_settlements(env: Env, group_id: u64) -> Vec<Settlement> {
-        let balances = Self::get_balances(env.clone(), group_id);
-        compute_optimal_settlements(&env, &balances)
+    /// Kesin minimum-transaction çözücüyü kullanır (büyük gruplarda Greedy'e düşer);
+    /// her token için ayrı plan üretilir (ana token önce, ardından izin listesindeki sırayla).
+    /// Dönen `id`, planın dayandığı grup durumunun özetidir; `settle_group`'a verilir.
+    pub fn compute_settlements(env: Env, group_id: u64) -> Result<SettlementPlan, Error> {
+        Self::compute_settlements_with(env, group_id, SettlementSolver::Exact)
+    }
+
+    /// Uzlaşma planını seçilen algoritmayla hesaplar (bkz. `SettlementSolver`).
+    pub fn compute_settlements_with(env: Env, group_id: u64, solver: SettlementSolver) -> Result<SettlementPlan, Error> {
+        let group = get_group(&env, group_id)?;
+        let mut all = Map::new(&env);
+        let mut settlements: Vec<Settlement> = Vec::new(&env);
+        for token in group.tokens.iter() {
+            let balances = Self::token_balances(&env, group_id, &token)?;
+            settlements.append(&solve_settlements(&env, solver, &token, &balances));
+            all.set(token, balances);
+        }
+
+        // Plan kimliği: harcama/ödeme sayaçları + token bazında bakiyeler + çözücü.
+        // Bu durumu değiştiren her yazım (yeni harcama, düzenleme, ödeme) farklı bir id üretir.
+        let snapshot = (
+            group_id,
+            get_next_expense_id(&env, group_id),
+            get_next_payment_id(&env, group_id),
+            solver,
+            all,
+        );
+        let id: BytesN<32> = env.crypto().sha256(&snapshot.to_xdr(&env)).into();
+
+        Ok(SettlementPlan { id, solver, settlements })
+    }
+
+    // ─────────────────────────────────────────────
+    //  KISMİ / EŞLER ARASI ÖDEME
+    // ─────────────────────────────────────────────
+
+    /// Borçlu `from`, alacaklı `to`'ya grubun ana token'ıyla geri ödeme yapar.
+    /// Bkz. `record_token_payment`.
+    pub fn record_payment(env: Env, group_id: u64, from: Address, to: Address, amount: i128) -> Result<u64, Error> {
+        let group = get_group(&env, group_id)?;
+        Self::record_token_payment(env, group_id, from, to, group.token, amount)
+    }
+
+    /// Borçlu `from`, alacaklı `to`'ya belirtilen token'la geri ödeme yapar.
+    /// Tutar token üzerinden gerçekten transfer edilir ve net bakiyelere işlenir;
+    /// grup açık kalır. `from` borçlu, `to` alacaklı olmalı ve tutar ikisinin
+    /// açık bakiyesini aşmamalıdır. Döndürdüğü değer: payment_id
+    pub fn record_token_payment(
+        env: Env,
+        group_id: u64,
+        from: Address,
+        to: Address,
+        token: Address,
+        amount: i128,
+    ) -> Result<u64, Error> {
+        from.require_auth();
+
+        let group = get_group(&env, group_id)?;
+        if is_group_settled(&env, group_id) {
+            return Err(Error::GroupAlreadySettled);
+        }
+        if !group.members.contains(&from) || !group.members.contains(&to) {
+            return Err(Error::NotAMember);
+        }
+        if from == to {
+            return Err(Error::SelfPayment);
+        }
+        if amount <= 0 {
+            return Err(Error::InvalidAmount);
+        }
+        if !group.tokens.contains(&token) {
+            return Err(Error::TokenNotAllowed);
+        }
+
+        // Ödeme açık borcu / alacağı aşmamalı
+        let mut balances = Self::token_balances(&env, group_id, &token)?;
+        let debt = -balances.get(from.clone()).unwrap_or(0);
+        let credit = balances.get(to.clone()).unwrap_or(0);
+        if amount > debt || amount > credit {
+            return Err(Error::PaymentExceedsDebt);
+        }
+
+        let payment_id = Self::execute_payment(&env, group_id, &from, &to, &token, amount, &mut balances);
+        save_group_balances(&env, group_id, &token, &balances);
+
+        Ok(payment_id)
+    }
+
+    /// Doğrulanmış bir ödemeyi SAC ile aktarır, kaydeder ve `balances` üzerine uygular.
+    /// Bakiye önbelleğini kaydetmek çağırana aittir.
+    fn execute_payment(
+        env: &Env,
+        group_id: u64,
+        from: &Address,
+        to: &Address,
+        token: &Address,
+        amount: i128,
+        balances: &mut Map<Address, i128>,
+    ) -> u64 {
+        token::Client::new(env, token).transfer(from, to, &amount);
+
+        let payment_id = get_next_payment_id(env, group_id);
+        let payment = Payment {
+            id: payment_id,
+            from: from.clone(),
+            to: to.clone(),
+            amount,
+            token: token.clone(),
+        };
+        save_payment(env, group_id, &payment);
+        set_next_payment_id(env, group_id, payment_id + 1);
+
+        apply_payment(balances, &payment);
+
+        env.events().publish(
+            (Symbol::new(env, "payment_recorded"), group_id, payment_id),
+            (from.clone(), to.clone(), token.clone(), amount),
+        );
+
+        payment_id
+    }
+
+    /// Kayıtlı bir ödemeyi döndürür.
+    pub fn get_payment(env: Env, group_id: u64, payment_id: u64) -> Result<Payment, Error> {
+        get_payment(&env, group_id, payment_id)
     }
 
     // ─────────────────────────────────────────────
@@ -356,49 +2196,54 @@ impl StellarSplitContract {
     /// Grubu settle eder: optimal transferleri hesaplar ve
     /// Stellar native token (XLM) SAC üzerinden gerçek transferleri yapar.
     ///
-    /// Her borçlu kişinin bu contract'a `require_auth` vermiş olması gerekir.
-    /// `token_address`: XLM SAC contract adresi (testnet'te friendbot'tan alınır)
+    /// `plan_id`, borçluların onayladığı `compute_settlements` sonucunun id'sidir;
+    /// o andan beri grup durumu değiştiyse `Error::StalePlan` döner ve hiçbir transfer yapılmaz.
+    /// Settle'ı sadece grup adminleri başlatabilir; her borçlu kişinin bu contract'a
+    /// `require_auth` vermiş olması gerekir.
     pub fn settle_group(
         env: Env,
         group_id: u64,
         settler: Address,
-    ) -> Vec<Settlement> {
+        plan_id: BytesN<32>,
+    ) -> Result<Vec<Settlement>, Error> {
+        Self::settle_group_with(env, group_id, settler, SettlementSolver::Exact, plan_id)
+    }
+
+    /// `settle_group` ile aynı; planı `compute_settlements_with` ile seçilen çözücüyle üretir.
+    pub fn settle_group_with(
+        env: Env,
+        group_id: u64,
+        settler: Address,
+        solver: SettlementSolver,
+        plan_id: BytesN<32>,
+    ) -> Result<Vec<Settlement>, Error> {
         settler.require_auth();
 
         // Zaten settle edilmişse kabul etme
         if is_group_settled(&env, group_id) {
-            panic!("group is already settled");
+            return Err(Error::GroupAlreadySettled);
         }
 
-        // Settlement planını hesapla
-        let settlements = Self::compute_settlements(env.clone(), group_id);
+        // Settle'ı sadece grup adminleri başlatabilir
+        let group = get_group(&env, group_id)?;
+        Self::require_group_admin(&group, &settler)?;
 
-        // SAC token client oluştur
-        let group = get_group(&env, group_id);
-        let token_client = token::Client::new(&env, &group.token);
+        // Settlement planını hesapla ve onaylanan plan ile aynı durumdan geldiğini doğrula
+        let plan = Self::compute_settlements_with(env.clone(), group_id, solver)?;
+        if plan.id != plan_id {
+            return Err(Error::StalePlan);
+        }
+        let settlements = plan.settlements;
 
         // Her transfer için: from kişisi authorize etmeli
         for i in 0..settlements.len() {
             let s = settlements.get(i).unwrap();
             // Borçlu kişinin auth'u gerekli
             s.from.require_auth();
-            // SAC üzerinden transfer yap
-            token_client.transfer(&s.from, &s.to, &s.amount);
+            // Transferin kendi token'ının SAC'ı üzerinden transfer yap
+            token::Client::new(&env, &s.token).transfer(&s.from, &s.to, &s.amount);
         }
 
-        // ── Reward Users with SPLT (Inter-contract call) ──
-        // In a real scenario, the reward_token_id would be stored in the Group or Global state.
-        // Here we use a dummy address if not provided, or skip if not found.
-        let reward_token_id = group.token.clone(); // Self-rewarding for demo or dedicated token
-        
-        // We reward the settler for initiating the transaction
-        let reward_amount = 100_i128; // 100 SPLT
-        env.invoke_contract::<()>(
-            &reward_token_id,
-            &soroban_sdk::Symbol::new(&env, "mint"),
-            soroban_sdk::vec![&env, settler.into_val(&env), reward_amount.into_val(&env)],
-        );
-
         // Grubu settled olarak işaretle
         set_group_settled(&env, group_id, true);
 
@@ -408,12 +2253,34 @@ impl StellarSplitContract {
             settlements.len(),
         );
 
-        env.events().publish(
-            (Symbol::new(&env, "reward_minted"), settler.clone()),
-            reward_amount,
-        );
+        // ── Settle Ödülü (Inter-contract call) ──
+        // Ödül yapılandırılmamışsa settle ödülsüz tamamlanır. Grupta hiç transfer
+        // yoksa (ne plan ne de önceden kaydedilmiş ödeme) ödül verilmez; borçsuz
+        // gruplar açıp settle ederek ödül toplanamaz. Başarısız mint settle'ı geri almaz.
+        if let Some(config) = get_reward_config(&env) {
+            let mut payments = Vec::new(&env);
+            for pid in 0..get_next_payment_id(&env, group_id) {
+                payments.push_back(get_payment(&env, group_id, pid)?);
+            }
+            if settlements.is_empty() && payments.is_empty() {
+                return Ok(settlements);
+            }
+            let rewards = compute_rewards(&env, &config, &settler, &settlements, &payments)?;
+            let minter = token::StellarAssetClient::new(&env, &config.token);
+            for (recipient, amount) in rewards.iter() {
+                let event = if matches!(minter.try_mint(&recipient, &amount), Ok(Ok(()))) {
+                    "reward_minted"
+                } else {
+                    "reward_failed"
+                };
+                env.events().publish(
+                    (Symbol::new(&env, event), recipient),
+                    amount,
+                );
+            }
+        }
 
-        settlements
+        Ok(settlements)
     }
 
     // ─────────────────────────────────────────────
@@ -421,15 +2288,102 @@ impl StellarSplitContract {
     // ─────────────────────────────────────────────
 
     /// Grup bilgisini döndürür.
-    pub fn get_group(env: Env, group_id: u64) -> Group {
+    pub fn get_group(env: Env, group_id: u64) -> Result<Group, Error> {
         get_group(&env, group_id)
     }
 
     /// Belirli bir harcamayı döndürür.
-    pub fn get_expense(env: Env, group_id: u64, expense_id: u64) -> Expense {
+    pub fn get_expense(env: Env, group_id: u64, expense_id: u64) -> Result<Expense, Error> {
         get_expense(&env, group_id, expense_id)
     }
 
+    /// Harcamanın makbuz referansını döndürür (eklenmemişse `None`).
+    pub fn get_receipt(env: Env, group_id: u64, expense_id: u64) -> Result<Option<Receipt>, Error> {
+        get_expense(&env, group_id, expense_id)?;
+        Ok(get_receipt(&env, group_id, expense_id))
+    }
+
+    /// Harcamaları `start` id'sinden itibaren sayfa sayfa döndürür; silinmiş
+    /// (tombstone) harcamalar atlanır. `limit` 1..=50 aralığına sıkıştırılır.
+    pub fn list_expenses(env: Env, group_id: u64, start: u64, limit: u32) -> Result<ExpensePage, Error> {
+        Self::query_expenses(
+            env,
+            group_id,
+            ExpenseFilter::default(),
+            start,
+            limit,
+        )
+    }
+
+    /// `list_expenses` ile aynı, sadece filtreye uyan harcamaları döndürür.
+    /// Bir çağrı en fazla 200 kayıt okur; sayfa dolmadan da `next` dönebilir.
+    /// Sayfadaki harcamaların makbuzları `receipts` içinde id'ye göre döner.
+    pub fn query_expenses(
+        env: Env,
+        group_id: u64,
+        filter: ExpenseFilter,
+        start: u64,
+        limit: u32,
+    ) -> Result<ExpensePage, Error> {
+        get_group(&env, group_id)?;
+        let limit = limit.clamp(1, MAX_EXPENSE_PAGE);
+        let end = get_next_expense_id(&env, group_id);
+        let scan_end = end.min(start.saturating_add(MAX_EXPENSE_SCAN));
+
+        let mut expenses: Vec<Expense> = Vec::new(&env);
+        let mut receipts: Map<u64, Receipt> = Map::new(&env);
+        let mut eid = start;
+        while eid < scan_end && expenses.len() < limit {
+            let expense = get_expense(&env, group_id, eid)?;
+            if !expense.deleted && Self::matches_filter(&expense, &filter) {
+                if let Some(receipt) = get_receipt(&env, group_id, eid) {
+                    receipts.set(eid, receipt);
+                }
+                expenses.push_back(expense);
+            }
+            eid += 1;
+        }
+
+        let next = if eid < end { Some(eid) } else { None };
+        Ok(ExpensePage { expenses, receipts, next })
+    }
+
+    /// Harcamanın filtredeki tüm dolu alanlara uyup uymadığı.
+    fn matches_filter(expense: &Expense, filter: &ExpenseFilter) -> bool {
+        if let Some(payer) = &filter.payer {
+            if !expense.contributions.contains_key(payer.clone()) {
+                return false;
+            }
+        }
+        if let Some(member) = &filter.member {
+            if !expense.contributions.contains_key(member.clone()) && !expense.split_among.contains(member) {
+                return false;
+            }
+        }
+        if let Some(category) = &filter.category {
+            if expense.category != *category {
+                return false;
+            }
+        }
+        if filter.submitter.as_ref().is_some_and(|s| expense.submitter != *s) {
+            return false;
+        }
+        let created = expense.created.timestamp;
+        if filter.created_from.is_some_and(|t| created < t) || filter.created_to.is_some_and(|t| created > t) {
+            return false;
+        }
+        if filter.occurred_from.is_some() || filter.occurred_to.is_some() {
+            let occurred = match expense.occurred_at {
+                Some(t) => t,
+                None => return false,
+            };
+            if filter.occurred_from.is_some_and(|t| occurred < t) || filter.occurred_to.is_some_and(|t| occurred > t) {
+                return false;
+            }
+        }
+        true
+    }
+
     /// Grubun settle durumunu döndürür.
     pub fn is_settled(env: Env, group_id: u64) -> bool {
         is_group_settled(&env, group_id)
@@ -440,14 +2394,17 @@ impl StellarSplitContract {
     // ─────────────────────────────────────────────
 
     /// Kullanıcının vasilerini ve onay eşiğini belirler.
-    pub fn set_guardians(env: Env, user: Address, guardians: Vec<Address>, threshold: u32) {
+    pub fn set_guardians(env: Env, user: Address, guardians: Vec<Address>, threshold: u32) -> Result<(), FeatureError> {
         user.require_auth();
 
         if guardians.is_empty() {
-            panic!("at least one guardian required");
+            return Err(FeatureError::NoGuardians);
+        }
+        if guardians.len() > MAX_GUARDIANS {
+            return Err(FeatureError::TooManyGuardians);
         }
         if threshold == 0 || threshold > guardians.len() {
-            panic!("invalid threshold");
+            return Err(FeatureError::InvalidThreshold);
         }
 
         let config = GuardianConfig {
@@ -462,13 +2419,15 @@ impl StellarSplitContract {
             (Symbol::new(&env, "guardians_set"), user),
             threshold,
         );
+
+        Ok(())
     }
 
     /// Bir vasi tarafından hesap kurtarma süreci başlatılır.
-    pub fn initiate_recovery(env: Env, guardian: Address, target: Address, new_address: Address) {
+    pub fn initiate_recovery(env: Env, guardian: Address, target: Address, new_address: Address) -> Result<(), FeatureError> {
         guardian.require_auth();
 
-        let config = get_guardian_config(&env, &target).expect("no guardians set for target");
+        let config = get_guardian_config(&env, &target).ok_or(FeatureError::GuardiansNotSet)?;
         
         // Çağıran kişi vasi mi?
         let mut is_guardian = false;
@@ -479,7 +2438,7 @@ impl StellarSplitContract {
             }
         }
         if !is_guardian {
-            panic!("caller is not a guardian");
+            return Err(FeatureError::NotAGuardian);
         }
 
         // Mevcut bir talep var mı? Varsa ve tamamlanmamışsa hata ver (veya üzerine yaz).
@@ -500,18 +2459,20 @@ impl StellarSplitContract {
             (Symbol::new(&env, "recovery_initiated"), target),
             guardian,
         );
+
+        Ok(())
     }
 
     /// Diğer vasiler kurtarma isteğini onaylar.
-    pub fn approve_recovery(env: Env, guardian: Address, target: Address) {
+    pub fn approve_recovery(env: Env, guardian: Address, target: Address) -> Result<(), FeatureError> {
         guardian.require_auth();
 
-        let mut request = get_recovery_request(&env, &target).expect("no active recovery request");
+        let mut request = get_recovery_request(&env, &target).ok_or(FeatureError::RecoveryNotFound)?;
         if request.status != 0 {
-            panic!("recovery request is not pending");
+            return Err(FeatureError::RecoveryNotPending);
         }
 
-        let config = get_guardian_config(&env, &target).expect("no guardians set for target");
+        let config = get_guardian_config(&env, &target).ok_or(FeatureError::GuardiansNotSet)?;
 
         // Çağıran kişi vasi mi?
         let mut is_guardian = false;
@@ -522,13 +2483,13 @@ impl StellarSplitContract {
             }
         }
         if !is_guardian {
-            panic!("caller is not a guardian");
+            return Err(FeatureError::NotAGuardian);
         }
 
         // Zaten onaylamış mı?
         for i in 0..request.approvals.len() {
             if request.approvals.get(i).unwrap() == guardian {
-                panic!("already approved");
+                return Err(FeatureError::AlreadyApproved);
             }
         }
 
@@ -547,6 +2508,8 @@ impl StellarSplitContract {
             (Symbol::new(&env, "recovery_approved"), target),
             request.status,
         );
+
+        Ok(())
     }
 
     pub fn get_guardians(env: Env, user: Address) -> Option<GuardianConfig> {
@@ -561,12 +2524,12 @@ impl StellarSplitContract {
     //  DEFI YIELD (VAULT)
     // ─────────────────────────────────────────────
 
-    pub fn stake(env: Env, group_id: u64, caller: Address, amount: i128) {
+    pub fn stake(env: Env, group_id: u64, caller: Address, amount: i128) -> Result<(), FeatureError> {
         caller.require_auth();
         if amount <= 0 {
-            panic!("amount must be positive");
+            return Err(FeatureError::InvalidAmount);
         }
-        let group = get_group(&env, group_id);
+        let group = get_group(&env, group_id).map_err(|_| FeatureError::GroupNotFound)?;
         
         let mut is_member = false;
         for i in 0..group.members.len() {
@@ -576,7 +2539,7 @@ impl StellarSplitContract {
             }
         }
         if !is_member {
-            panic!("only members can stake");
+            return Err(FeatureError::NotAMember);
         }
 
         let token_client = token::Client::new(&env, &group.token);
@@ -590,14 +2553,16 @@ impl StellarSplitContract {
         storage::save_vault(&env, group_id, &vault);
 
         env.events().publish((Symbol::new(&env, "vault_staked"), group_id), amount);
+
+        Ok(())
     }
 
-    pub fn withdraw(env: Env, group_id: u64, caller: Address, amount: i128) {
+    pub fn withdraw(env: Env, group_id: u64, caller: Address, amount: i128) -> Result<(), FeatureError> {
         caller.require_auth();
         if amount <= 0 {
-            panic!("amount must be positive");
+            return Err(FeatureError::InvalidAmount);
         }
-        let group = get_group(&env, group_id);
+        let group = get_group(&env, group_id).map_err(|_| FeatureError::GroupNotFound)?;
         
         let mut is_member = false;
         for i in 0..group.members.len() {
@@ -607,14 +2572,14 @@ impl StellarSplitContract {
             }
         }
         if !is_member {
-            panic!("only members can withdraw");
+            return Err(FeatureError::NotAMember);
         }
 
         let mut vault = storage::get_vault(&env, group_id);
         vault = Self::compute_yield(&env, vault);
         
         if vault.total_staked + vault.yield_earned < amount {
-            panic!("insufficient vault balance");
+            return Err(FeatureError::InsufficientVaultBalance);
         }
 
         let token_client = token::Client::new(&env, &group.token);
@@ -635,14 +2600,16 @@ impl StellarSplitContract {
         storage::save_vault(&env, group_id, &vault);
 
         env.events().publish((Symbol::new(&env, "vault_withdrawn"), group_id), amount);
+
+        Ok(())
     }
 
-    pub fn donate_yield(env: Env, group_id: u64, caller: Address, amount: i128, donation_address: Address) {
+    pub fn donate_yield(env: Env, group_id: u64, caller: Address, amount: i128, donation_address: Address) -> Result<(), FeatureError> {
         caller.require_auth();
         if amount <= 0 {
-            panic!("amount must be positive");
+            return Err(FeatureError::InvalidAmount);
         }
-        let group = get_group(&env, group_id);
+        let group = get_group(&env, group_id).map_err(|_| FeatureError::GroupNotFound)?;
         
         let mut is_member = false;
         for i in 0..group.members.len() {
@@ -652,14 +2619,14 @@ impl StellarSplitContract {
             }
         }
         if !is_member {
-            panic!("only members can donate yield");
+            return Err(FeatureError::NotAMember);
         }
 
         let mut vault = storage::get_vault(&env, group_id);
         vault = Self::compute_yield(&env, vault);
         
         if vault.yield_earned < amount {
-            panic!("insufficient yield to donate");
+            return Err(FeatureError::InsufficientYield);
         }
 
         // Send yield to donation address
@@ -673,6 +2640,8 @@ impl StellarSplitContract {
         storage::save_vault(&env, group_id, &vault);
 
         env.events().publish((Symbol::new(&env, "yield_donated"), group_id), amount);
+
+        Ok(())
     }
 
     pub fn get_vault(env: Env, group_id: u64) -> Vault {
@@ -745,15 +2714,15 @@ impl StellarSplitContract {
         creator: Address,
         goal_amount: i128,
         deadline: u64,
-    ) -> SavingsPool {
+    ) -> Result<SavingsPool, FeatureError> {
         creator.require_auth();
 
         if goal_amount <= 0 {
-            panic!("goal amount must be positive");
+            return Err(FeatureError::InvalidAmount);
         }
 
         // Creator grupta mı?
-        let group = get_group(&env, group_id);
+        let group = get_group(&env, group_id).map_err(|_| FeatureError::GroupNotFound)?;
         let mut creator_in_group = false;
         for i in 0..group.members.len() {
             if group.members.get(i).unwrap() == creator {
@@ -762,19 +2731,19 @@ impl StellarSplitContract {
             }
         }
         if !creator_in_group {
-            panic!("creator is not a group member");
+            return Err(FeatureError::NotAMember);
         }
 
         // Zaten aktif bir havuz var mı?
         if let Some(existing) = get_savings_pool(&env, group_id) {
             if existing.status == 0 {
-                panic!("group already has an active savings pool");
+                return Err(FeatureError::PoolAlreadyActive);
             }
         }
 
         // Deadline kontrol: 0 ise süresiz; değilse gelecekte olmalı
         if deadline > 0 && deadline <= env.ledger().timestamp() {
-            panic!("deadline must be in the future");
+            return Err(FeatureError::InvalidDeadline);
         }
 
         let pool = SavingsPool {
@@ -793,7 +2762,7 @@ impl StellarSplitContract {
             (creator, goal_amount, deadline),
         );
 
-        pool
+        Ok(pool)
     }
 
     /// Savings pool'a katkı ekler. Token transferini gerçekleştirir.
@@ -803,15 +2772,15 @@ impl StellarSplitContract {
         group_id: u64,
         contributor: Address,
         amount: i128,
-    ) -> SavingsPool {
+    ) -> Result<SavingsPool, FeatureError> {
         contributor.require_auth();
 
         if amount <= 0 {
-            panic!("contribution amount must be positive");
+            return Err(FeatureError::InvalidAmount);
         }
 
         // Contributor grupta mı?
-        let group = get_group(&env, group_id);
+        let group = get_group(&env, group_id).map_err(|_| FeatureError::GroupNotFound)?;
         let mut in_group = false;
         for i in 0..group.members.len() {
             if group.members.get(i).unwrap() == contributor {
@@ -820,13 +2789,13 @@ impl StellarSplitContract {
             }
         }
         if !in_group {
-            panic!("contributor is not a group member");
+            return Err(FeatureError::NotAMember);
         }
 
-        let mut pool = get_savings_pool(&env, group_id).expect("no savings pool for this group");
+        let mut pool = get_savings_pool(&env, group_id).ok_or(FeatureError::PoolNotFound)?;
 
         if pool.status != 0 {
-            panic!("savings pool is not active");
+            return Err(FeatureError::PoolNotActive);
         }
 
         // Token transferi: contributor → contract
@@ -851,7 +2820,7 @@ impl StellarSplitContract {
             (contributor, amount),
         );
 
-        pool
+        Ok(pool)
     }
 
     /// Savings pool'u serbest bırakır (release).
@@ -862,10 +2831,10 @@ impl StellarSplitContract {
         env: Env,
         group_id: u64,
         caller: Address,
-    ) -> i128 {
+    ) -> Result<i128, FeatureError> {
         caller.require_auth();
 
-        let group = get_group(&env, group_id);
+        let group = get_group(&env, group_id).map_err(|_| FeatureError::GroupNotFound)?;
 
         // Caller grupta mı?
         let mut in_group = false;
@@ -876,25 +2845,25 @@ impl StellarSplitContract {
             }
         }
         if !in_group {
-            panic!("caller is not a group member");
+            return Err(FeatureError::NotAMember);
         }
 
-        let mut pool = get_savings_pool(&env, group_id).expect("no savings pool for this group");
+        let mut pool = get_savings_pool(&env, group_id).ok_or(FeatureError::PoolNotFound)?;
 
         if pool.status == 2 {
-            panic!("savings pool is already cancelled");
+            return Err(FeatureError::PoolCancelled);
         }
 
         // Sadece creator veya goal tamamlanmışsa herkes release edebilir
         if pool.status == 0 && pool.creator != caller {
-            panic!("only the creator can release an active pool before goal is reached");
+            return Err(FeatureError::NotPoolCreator);
         }
 
         let total = pool.current_amount;
         if total <= 0 {
             pool.status = 2; // Cancelled — empty pool
             save_savings_pool(&env, group_id, &pool);
-            return 0;
+            return Ok(0);
         }
 
         // Üyelere eşit dağıt
@@ -925,7 +2894,7 @@ impl StellarSplitContract {
             (caller, total),
         );
 
-        total
+        Ok(total)
     }
 
     /// Savings pool bilgisini döndürür.
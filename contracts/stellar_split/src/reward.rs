@@ -0,0 +1,115 @@
+use soroban_sdk::{Address, Env, Map, Vec};
+
+use crate::errors::{AdminError, Error};
+use crate::split::BASIS_POINTS_TOTAL;
+use crate::types::{Payment, RewardConfig, RewardRecipients, Settlement};
+
+/// Ödül yapılandırmasının tutarlı olduğunu doğrular.
+pub fn validate_reward_config(config: &RewardConfig) -> Result<(), AdminError> {
+    if config.base_amount < 0 || config.per_transfer < 0 {
+        return Err(AdminError::InvalidRewardConfig);
+    }
+    if config.volume_bps as i128 > BASIS_POINTS_TOTAL {
+        return Err(AdminError::InvalidRewardConfig);
+    }
+    Ok(())
+}
+
+/// Settle edilen plan için alıcı başına ödül tutarlarını hesaplar.
+///
+/// Alıcılar `config.recipients` moduna göre seçilir ve ilk görülme sırasıyla
+/// döner; `max_recipients` sıfırdan büyükse liste o uzunlukta kesilir.
+/// Ödülü sıfır olan alıcılar listeye alınmaz. Token'ların ondalıkları farklı
+/// olabileceği için hacme yalnızca ödül token'ındaki transferler katılır;
+/// diğer token'lardaki transferler sadece transfer sayısına eklenir.
+pub fn compute_rewards(
+    env: &Env,
+    config: &RewardConfig,
+    settler: &Address,
+    settlements: &Vec<Settlement>,
+    payments: &Vec<Payment>,
+) -> Result<Vec<(Address, i128)>, Error> {
+    // Alıcı → (transfer sayısı, hacim); `order` ilk görülme sırasını tutar
+    let mut order: Vec<Address> = Vec::new(env);
+    let mut activity: Map<Address, (i128, i128)> = Map::new(env);
+
+    match config.recipients {
+        RewardRecipients::Settler => {
+            // Settler tüm planın sayısı ve hacmi üzerinden ödüllendirilir
+            let mut volume: i128 = 0;
+            for s in settlements.iter() {
+                let amount = reward_volume(config, &s.token, s.amount);
+                volume = volume.checked_add(amount).ok_or(Error::InvalidAmount)?;
+            }
+            order.push_back(settler.clone());
+            activity.set(settler.clone(), (settlements.len() as i128, volume));
+        }
+        RewardRecipients::Debtors => {
+            for s in settlements.iter() {
+                let amount = reward_volume(config, &s.token, s.amount);
+                tally(&mut order, &mut activity, s.from, amount)?;
+            }
+        }
+        RewardRecipients::FastestPayers => {
+            for p in payments.iter() {
+                let amount = reward_volume(config, &p.token, p.amount);
+                tally(&mut order, &mut activity, p.from, amount)?;
+            }
+        }
+    }
+
+    let mut rewards: Vec<(Address, i128)> = Vec::new(env);
+    for who in order.iter() {
+        if config.max_recipients > 0 && rewards.len() >= config.max_recipients {
+            break;
+        }
+        let (transfers, volume) = activity.get(who.clone()).unwrap();
+        let amount = reward_amount(config, transfers, volume)?;
+        if amount > 0 {
+            rewards.push_back((who, amount));
+        }
+    }
+    Ok(rewards)
+}
+
+/// `base_amount + per_transfer * transfers + volume * volume_bps / 10_000`
+fn reward_amount(config: &RewardConfig, transfers: i128, volume: i128) -> Result<i128, Error> {
+    let per_transfer = config.per_transfer.checked_mul(transfers).ok_or(Error::InvalidAmount)?;
+    let by_volume = volume
+        .checked_mul(config.volume_bps as i128)
+        .ok_or(Error::InvalidAmount)?
+        / BASIS_POINTS_TOTAL;
+    config
+        .base_amount
+        .checked_add(per_transfer)
+        .and_then(|v| v.checked_add(by_volume))
+        .ok_or(Error::InvalidAmount)
+}
+
+/// Transferin hacme katkısı: ödül token'ında tutarın kendisi, diğer token'larda 0.
+fn reward_volume(config: &RewardConfig, token: &Address, amount: i128) -> i128 {
+    if *token == config.token {
+        amount
+    } else {
+        0
+    }
+}
+
+/// Bir alıcının transfer sayısını ve hacmini günceller.
+fn tally(
+    order: &mut Vec<Address>,
+    activity: &mut Map<Address, (i128, i128)>,
+    who: Address,
+    amount: i128,
+) -> Result<(), Error> {
+    let (count, volume) = match activity.get(who.clone()) {
+        Some(a) => a,
+        None => {
+            order.push_back(who.clone());
+            (0, 0)
+        }
+    };
+    let volume = volume.checked_add(amount).ok_or(Error::InvalidAmount)?;
+    activity.set(who, (count + 1, volume));
+    Ok(())
+}
@@ -1,23 +1,24 @@
 use soroban_sdk::{Address, Env, Map, Vec};
 
-use crate::types::Settlement;
+use crate::types::{Settlement, SettlementSolver};
 
 /// Greedy min-transaction borç grafı çözüm algoritması.
 ///
-/// Net bakiyeleri alır, borçluları ve alacaklıları ayırır,
-/// en büyük borçlu ile en büyük alacaklıyı eşleştirerek
-/// minimum sayıda transfer üretir.
+/// Net bakiyeleri alır, borçluları ve alacaklıları ayırır; her adımda kalan
+/// en büyük borçlu ile kalan en büyük alacaklıyı eşleştirerek transfer üretir.
+/// Eşit tutarlarda adres sırası (Map anahtar sırası) kullanılır, böylece çıktı
+/// deterministiktir.
 ///
 /// Garanti: N kişi için en fazla N-1 transfer.
-pub fn compute_optimal_settlements(env: &Env, balances: &Map<Address, i128>) -> Vec<Settlement> {
+/// Bakiyeler tek bir token cinsindendir; üretilen transferler `token` ile etiketlenir.
+pub fn compute_optimal_settlements(env: &Env, token: &Address, balances: &Map<Address, i128>) -> Vec<Settlement> {
     let mut settlements: Vec<Settlement> = Vec::new(env);
 
-    // Borçluları ve alacaklıları ayır
+    // Borçluları ve alacaklıları ayır (adres sırasında)
     let mut debtors: Vec<(Address, i128)> = Vec::new(env);   // negatif bakiyeli (borçlu)
     let mut creditors: Vec<(Address, i128)> = Vec::new(env);  // pozitif bakiyeli (alacaklı)
 
-    for key in balances.keys() {
-        let balance = balances.get(key.clone()).unwrap();
+    for (key, balance) in balances.iter() {
         if balance < 0 {
             debtors.push_back((key, -balance)); // pozitife çevir, borç miktarı
         } else if balance > 0 {
@@ -26,23 +27,10 @@ pub fn compute_optimal_settlements(env: &Env, balances: &Map<Address, i128>) ->
         // balance == 0 olan kişiler zaten tasfiye olmuş, atla
     }
 
-    // Greedy eşleştirme
-    let mut d_idx: u32 = 0;
-    let mut c_idx: u32 = 0;
-
-    // Mutable bakiyeler için kopyalar
-    let mut d_amounts: Vec<i128> = Vec::new(env);
-    let mut c_amounts: Vec<i128> = Vec::new(env);
-    for i in 0..debtors.len() {
-        d_amounts.push_back(debtors.get(i).unwrap().1);
-    }
-    for i in 0..creditors.len() {
-        c_amounts.push_back(creditors.get(i).unwrap().1);
-    }
-
-    while d_idx < debtors.len() && c_idx < creditors.len() {
-        let d_remaining = d_amounts.get(d_idx).unwrap();
-        let c_remaining = c_amounts.get(c_idx).unwrap();
+    // Greedy eşleştirme: her turda en büyük borçlu ↔ en büyük alacaklı
+    while let (Some(d_idx), Some(c_idx)) = (index_of_largest(&debtors), index_of_largest(&creditors)) {
+        let (debtor, d_remaining) = debtors.get(d_idx).unwrap();
+        let (creditor, c_remaining) = creditors.get(c_idx).unwrap();
 
         let transfer_amount = if d_remaining < c_remaining {
             d_remaining
@@ -50,27 +38,171 @@ pub fn compute_optimal_settlements(env: &Env, balances: &Map<Address, i128>) ->
             c_remaining
         };
 
-        if transfer_amount > 0 {
-            settlements.push_back(Settlement {
-                from: debtors.get(d_idx).unwrap().0,
-                to: creditors.get(c_idx).unwrap().0,
-                amount: transfer_amount,
-            });
-        }
+        settlements.push_back(Settlement {
+            from: debtor.clone(),
+            to: creditor.clone(),
+            amount: transfer_amount,
+            token: token.clone(),
+        });
 
-        // Bakiyeleri güncelle
+        // Bakiyeleri güncelle; kapanan tarafı listeden çıkar
         let new_d = d_remaining - transfer_amount;
         let new_c = c_remaining - transfer_amount;
-        d_amounts.set(d_idx, new_d);
-        c_amounts.set(c_idx, new_c);
-
         if new_d == 0 {
-            d_idx += 1;
+            debtors.remove(d_idx);
+        } else {
+            debtors.set(d_idx, (debtor, new_d));
         }
         if new_c == 0 {
-            c_idx += 1;
+            creditors.remove(c_idx);
+        } else {
+            creditors.set(c_idx, (creditor, new_c));
+        }
+    }
+
+    settlements
+}
+
+/// Tek bir borçlunun (`member`) tüm borcunu kapatan transferleri üretir.
+///
+/// Borç, kalan en büyük alacaklıdan başlanarak alacaklılara dağıtılır; diğer
+/// borçluların bakiyelerine dokunulmaz. Bakiyeler sıfır toplamlı olduğundan
+/// alacaklıların toplamı borcu her zaman karşılar.
+pub fn compute_exit_settlements(
+    env: &Env,
+    token: &Address,
+    balances: &Map<Address, i128>,
+    member: &Address,
+) -> Vec<Settlement> {
+    let mut settlements: Vec<Settlement> = Vec::new(env);
+    let mut remaining = -balances.get(member.clone()).unwrap_or(0);
+
+    let mut creditors: Vec<(Address, i128)> = Vec::new(env);
+    for (key, balance) in balances.iter() {
+        if balance > 0 {
+            creditors.push_back((key, balance));
+        }
+    }
+
+    while remaining > 0 {
+        let Some(idx) = index_of_largest(&creditors) else {
+            break;
+        };
+        let (creditor, credit) = creditors.get(idx).unwrap();
+        let amount = if remaining < credit { remaining } else { credit };
+        settlements.push_back(Settlement {
+            from: member.clone(),
+            to: creditor,
+            amount,
+            token: token.clone(),
+        });
+        remaining -= amount;
+        creditors.remove(idx);
+    }
+
+    settlements
+}
+
+/// En büyük tutarlı girişin indeksini döndürür; eşitlikte ilk (adres sırasında önce gelen) seçilir.
+fn index_of_largest(entries: &Vec<(Address, i128)>) -> Option<u32> {
+    let mut best: Option<(u32, i128)> = None;
+    for (i, (_, amount)) in entries.iter().enumerate() {
+        match best {
+            Some((_, best_amount)) if amount <= best_amount => {}
+            _ => best = Some((i as u32, amount)),
+        }
+    }
+    best.map(|(i, _)| i)
+}
+
+/// Kesin çözücünün çalışacağı en fazla sıfırdan farklı bakiye sayısı.
+/// Durum uzayı 2^N olduğundan üstünde Greedy algoritmaya düşülür.
+pub const MAX_EXACT_PARTICIPANTS: u32 = 12;
+
+/// Kesin minimum-transaction çözümü.
+///
+/// Sıfır toplamlı her alt küme k kişi için k-1 transferle kapatılabilir; bu yüzden
+/// minimum transfer sayısı = N - (ayrık sıfır toplamlı alt kümelerin azami sayısı).
+/// Bit maskesi DP ile bu bölümleme bulunur, her alt küme kendi içinde Greedy ile
+/// çözülür. Sıfırdan farklı bakiye sayısı `MAX_EXACT_PARTICIPANTS`'ı aşarsa
+/// doğrudan `compute_optimal_settlements` kullanılır.
+pub fn compute_exact_settlements(env: &Env, token: &Address, balances: &Map<Address, i128>) -> Vec<Settlement> {
+    let mut members: Vec<Address> = Vec::new(env);
+    let mut amounts: Vec<i128> = Vec::new(env);
+    for (addr, balance) in balances.iter() {
+        if balance != 0 {
+            members.push_back(addr);
+            amounts.push_back(balance);
+        }
+    }
+
+    let n = members.len();
+    if n > MAX_EXACT_PARTICIPANTS {
+        return compute_optimal_settlements(env, token, balances);
+    }
+
+    let full: usize = (1 << n) - 1;
+    let mask_sum = |mask: usize| -> i128 {
+        let mut sum: i128 = 0;
+        for i in 0..n {
+            if mask & (1 << i) != 0 {
+                sum += amounts.get(i).unwrap();
+            }
+        }
+        sum
+    };
+
+    // dp[mask]: mask içindeki kişilerin bölünebildiği azami sıfır toplamlı grup sayısı
+    let mut dp = [0u8; 1 << MAX_EXACT_PARTICIPANTS];
+    for mask in 1..=full {
+        let mut best = 0u8;
+        for i in 0..n {
+            if mask & (1 << i) != 0 && dp[mask ^ (1 << i)] > best {
+                best = dp[mask ^ (1 << i)];
+            }
+        }
+        dp[mask] = best + if mask_sum(mask) == 0 { 1 } else { 0 };
+    }
+
+    // Geri izleme: kişileri çıkarma sırası, sıfır toplamlı önekleri grup sınırı yapar
+    let mut order: Vec<u32> = Vec::new(env);
+    let mut mask = full;
+    while mask != 0 {
+        let target = dp[mask] - if mask_sum(mask) == 0 { 1 } else { 0 };
+        for i in 0..n {
+            if mask & (1 << i) != 0 && dp[mask ^ (1 << i)] == target {
+                order.push_front(i);
+                mask ^= 1 << i;
+                break;
+            }
+        }
+    }
+
+    let mut settlements: Vec<Settlement> = Vec::new(env);
+    let mut group: Map<Address, i128> = Map::new(env);
+    let mut running: i128 = 0;
+    for i in order.iter() {
+        let amount = amounts.get(i).unwrap();
+        group.set(members.get(i).unwrap(), amount);
+        running += amount;
+        if running == 0 {
+            settlements.append(&compute_optimal_settlements(env, token, &group));
+            group = Map::new(env);
         }
     }
 
     settlements
 }
+
+/// Seçilen algoritmayla uzlaşma planı üretir.
+pub fn solve_settlements(
+    env: &Env,
+    solver: SettlementSolver,
+    token: &Address,
+    balances: &Map<Address, i128>,
+) -> Vec<Settlement> {
+    match solver {
+        SettlementSolver::Greedy => compute_optimal_settlements(env, token, balances),
+        SettlementSolver::Exact => compute_exact_settlements(env, token, balances),
+    }
+}
@@ -0,0 +1,167 @@
+use soroban_sdk::{Address, Env, Map, Vec};
+
+use crate::errors::Error;
+use crate::types::{Expense, Payment, RoundingPolicy, SplitMode};
+
+/// Yüzde modunda ağırlıkların toplamı (baz puan).
+pub const BASIS_POINTS_TOTAL: i128 = 10_000;
+
+/// Bir harcamanın `split_among` üyelerine düşen payları hesaplar.
+///
+/// Dönen vektör `split_among` ile aynı sıradadır. `split_values` moda göre
+/// doğrulanır: Equal için boş, diğer modlarda her üye için pozitif bir değer.
+pub fn compute_split_amounts(
+    env: &Env,
+    amount: i128,
+    mode: SplitMode,
+    split_among: &Vec<Address>,
+    split_values: &Vec<i128>,
+) -> Result<Vec<i128>, Error> {
+    let n = split_among.len();
+    let mut parts: Vec<i128> = Vec::new(env);
+
+    if mode == SplitMode::Equal {
+        if !split_values.is_empty() {
+            return Err(Error::SplitLengthMismatch);
+        }
+        let share = amount / (n as i128);
+        for _ in 0..n {
+            parts.push_back(share);
+        }
+        return Ok(parts);
+    }
+
+    if split_values.len() != n {
+        return Err(Error::SplitLengthMismatch);
+    }
+
+    let mut total: i128 = 0;
+    for v in split_values.iter() {
+        if v <= 0 {
+            return Err(Error::InvalidSplitValue);
+        }
+        total = total.checked_add(v).ok_or(Error::InvalidSplitValue)?;
+    }
+
+    match mode {
+        SplitMode::Exact => {
+            if total != amount {
+                return Err(Error::SplitSumMismatch);
+            }
+            return Ok(split_values.clone());
+        }
+        SplitMode::Percentage => {
+            if total != BASIS_POINTS_TOTAL {
+                return Err(Error::SplitSumMismatch);
+            }
+        }
+        SplitMode::Shares | SplitMode::Equal => {}
+    }
+
+    // Ağırlıklı bölüşüm: amount * ağırlık / toplam ağırlık
+    for v in split_values.iter() {
+        let part = amount
+            .checked_mul(v)
+            .ok_or(Error::InvalidAmount)?
+            / total;
+        parts.push_back(part);
+    }
+    Ok(parts)
+}
+
+/// Harcamada her kişinin borçlandığı tutarı döndürür (kalan dahil).
+///
+/// Taban paylar `compute_split_amounts` ile hesaplanır; tam bölünemeyen kalan
+/// harcamanın `rounding` politikasına göre tek bir kişiye eklenir. Sonuçtaki
+/// tutarların toplamı her zaman `expense.amount`'a eşittir.
+pub fn compute_owed(env: &Env, expense: &Expense) -> Result<Map<Address, i128>, Error> {
+    let parts = compute_split_amounts(
+        env,
+        expense.amount,
+        expense.split_mode,
+        &expense.split_among,
+        &expense.split_values,
+    )?;
+
+    let mut owed: Map<Address, i128> = Map::new(env);
+    let mut assigned: i128 = 0;
+    for i in 0..expense.split_among.len() {
+        let member = expense.split_among.get(i).unwrap();
+        let part = parts.get(i).unwrap();
+        owed.set(member.clone(), owed.get(member).unwrap_or(0) + part);
+        assigned += part;
+    }
+
+    let remainder = expense.amount - assigned;
+    if remainder != 0 {
+        let n = expense.split_among.len() as u64;
+        let target = match expense.rounding {
+            RoundingPolicy::Payer => expense.payer.clone(),
+            RoundingPolicy::FirstMember => expense.split_among.get(0).unwrap(),
+            RoundingPolicy::Rotate => expense.split_among.get((expense.id % n) as u32).unwrap(),
+        };
+        owed.set(target.clone(), owed.get(target).unwrap_or(0) + remainder);
+    }
+
+    Ok(owed)
+}
+
+/// Bir harcamanın net bakiye etkisini `balances` üzerine uygular.
+///
+/// `sign = 1` harcamayı ekler, `sign = -1` geri alır. Her payer katkısı kadar
+/// alacaklandırılır, her borçlu kendi payı kadar borçlandırılır.
+pub fn apply_expense(
+    env: &Env,
+    balances: &mut Map<Address, i128>,
+    expense: &Expense,
+    sign: i128,
+) -> Result<(), Error> {
+    let owed = compute_owed(env, expense)?;
+
+    for (payer, paid) in expense.contributions.iter() {
+        let current = balances.get(payer.clone()).unwrap_or(0);
+        balances.set(payer, current + sign * paid);
+    }
+
+    for (member, part) in owed.iter() {
+        let current = balances.get(member.clone()).unwrap_or(0);
+        balances.set(member, current - sign * part);
+    }
+    Ok(())
+}
+
+/// Bir geri ödemenin net bakiye etkisini uygular: ödeyenin borcu azalır,
+/// alıcının alacağı aynı tutarda düşer.
+pub fn apply_payment(balances: &mut Map<Address, i128>, payment: &Payment) {
+    let from_current = balances.get(payment.from.clone()).unwrap_or(0);
+    balances.set(payment.from.clone(), from_current + payment.amount);
+    let to_current = balances.get(payment.to.clone()).unwrap_or(0);
+    balances.set(payment.to.clone(), to_current - payment.amount);
+}
+
+/// Net bakiyelerin tam olarak sıfıra toplandığını doğrular.
+pub fn ensure_zero_sum(balances: &Map<Address, i128>) -> Result<(), Error> {
+    let mut total: i128 = 0;
+    for (_, balance) in balances.iter() {
+        total += balance;
+    }
+    if total != 0 {
+        return Err(Error::BalanceInvariantViolated);
+    }
+    Ok(())
+}
+
+/// İki bakiye haritasını karşılaştırır; eksik girişler 0 kabul edilir.
+pub fn balances_match(a: &Map<Address, i128>, b: &Map<Address, i128>) -> bool {
+    for (addr, v) in a.iter() {
+        if b.get(addr).unwrap_or(0) != v {
+            return false;
+        }
+    }
+    for (addr, v) in b.iter() {
+        if a.get(addr).unwrap_or(0) != v {
+            return false;
+        }
+    }
+    true
+}
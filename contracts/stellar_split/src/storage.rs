@@ -1,6 +1,7 @@
-use soroban_sdk::{Address, Env, Vec};
+use soroban_sdk::{Address, Env, Map, String, Vec};
 
-use crate::types::{DataKey, Expense, Group, GuardianConfig, RecoveryRequest, Vault, UserBadges, SavingsPool};
+use crate::errors::Error;
+use crate::types::{ApprovalPolicy, DataKey, Expense, ExpenseStatus, ExpenseSummary, ExpenseV0, MigrationCursor, Receipt, RecurringExpense, Group, GroupV0, GuardianConfig, Invite, Payment, RecoveryRequest, RewardConfig, Vault, UserBadges, SavingsPool};
 
 // ── TTL Sabitleri ──
 // Soroban'da persistent storage girişleri süresi dolar.
@@ -9,6 +10,13 @@ use crate::types::{DataKey, Expense, Group, GuardianConfig, RecoveryRequest, Vau
 const LIFETIME_THRESHOLD: u32 = 17_280; // ~1 gün (5 saniye/ledger)
 const BUMP_AMOUNT: u32 = 518_400;       // ~30 gün
 
+/// Instance storage (admin, global yapılandırma) TTL'sini uzatır.
+fn bump_instance(env: &Env) {
+    env.storage()
+        .instance()
+        .extend_ttl(LIFETIME_THRESHOLD, BUMP_AMOUNT);
+}
+
 /// TTL'yi güvenli şekilde uzatır — sadece key varsa çağrılmalı.
 fn bump_persistent(env: &Env, key: &DataKey) {
     env.storage()
@@ -33,6 +41,74 @@ pub fn set_next_group_id(env: &Env, id: u64) {
     bump_persistent(env, &key);
 }
 
+// ── Kontrat Admin & Global Yapılandırma ──
+
+/// Kontrat yöneticisini döndürür (initialize edilmemişse `None`).
+pub fn get_admin(env: &Env) -> Option<Address> {
+    bump_instance(env);
+    env.storage().instance().get(&DataKey::Admin)
+}
+
+/// Kontrat yöneticisini kaydeder.
+pub fn set_admin(env: &Env, admin: &Address) {
+    env.storage().instance().set(&DataKey::Admin, admin);
+    bump_instance(env);
+}
+
+/// Devri kabul bekleyen yöneticiyi döndürür.
+pub fn get_pending_admin(env: &Env) -> Option<Address> {
+    env.storage().instance().get(&DataKey::PendingAdmin)
+}
+
+/// Bekleyen yöneticiyi kaydeder; `None` bekleyen devri siler.
+pub fn set_pending_admin(env: &Env, admin: &Option<Address>) {
+    match admin {
+        Some(a) => env.storage().instance().set(&DataKey::PendingAdmin, a),
+        None => env.storage().instance().remove(&DataKey::PendingAdmin),
+    }
+    bump_instance(env);
+}
+
+/// Depolama şeması sürümü (sürümlemeden önceki deploylar için 0).
+pub fn get_storage_version(env: &Env) -> u32 {
+    env.storage().instance().get(&DataKey::StorageVersion).unwrap_or(0)
+}
+
+/// Depolama şeması sürümünü kaydeder.
+pub fn set_storage_version(env: &Env, version: u32) {
+    env.storage().instance().set(&DataKey::StorageVersion, &version);
+    bump_instance(env);
+}
+
+/// Yarım kalmış taşımanın devam konumu (taşıma yoksa `None`).
+pub fn get_migration_cursor(env: &Env) -> Option<MigrationCursor> {
+    env.storage().instance().get(&DataKey::MigrationCursor)
+}
+
+/// Taşımanın devam konumunu kaydeder; `None` konumu siler (taşıma bitti).
+pub fn set_migration_cursor(env: &Env, cursor: &Option<MigrationCursor>) {
+    match cursor {
+        Some(c) => env.storage().instance().set(&DataKey::MigrationCursor, c),
+        None => env.storage().instance().remove(&DataKey::MigrationCursor),
+    }
+    bump_instance(env);
+}
+
+/// Settle ödülü yapılandırmasını döndürür (yoksa ödül verilmez).
+pub fn get_reward_config(env: &Env) -> Option<RewardConfig> {
+    bump_instance(env);
+    env.storage().instance().get(&DataKey::RewardConfig)
+}
+
+/// Settle ödülü yapılandırmasını kaydeder; `None` yapılandırmayı siler.
+pub fn set_reward_config(env: &Env, config: &Option<RewardConfig>) {
+    match config {
+        Some(c) => env.storage().instance().set(&DataKey::RewardConfig, c),
+        None => env.storage().instance().remove(&DataKey::RewardConfig),
+    }
+    bump_instance(env);
+}
+
 // ── Group CRUD ──
 
 /// Grubu kaydeder.
@@ -42,16 +118,109 @@ pub fn save_group(env: &Env, group_id: u64, group: &Group) {
     bump_persistent(env, &key);
 }
 
-/// Grubu okur (yoksa panic).
-pub fn get_group(env: &Env, group_id: u64) -> Group {
+/// Grubu okur (yoksa `Error::GroupNotFound`).
+pub fn get_group(env: &Env, group_id: u64) -> Result<Group, Error> {
     let key = DataKey::Group(group_id);
     let group: Group = env
         .storage()
         .persistent()
         .get(&key)
-        .expect("group not found");
+        .ok_or(Error::GroupNotFound)?;
     bump_persistent(env, &key);
-    group
+    Ok(group)
+}
+
+/// Harcamayı v0 depolama düzeniyle okur (sadece `migrate` için).
+pub fn get_expense_v0(env: &Env, group_id: u64, expense_id: u64) -> Option<ExpenseV0> {
+    env.storage().persistent().get(&DataKey::Expense(group_id, expense_id))
+}
+
+/// Grubu v0 depolama düzeniyle okur (sadece `migrate` için).
+pub fn get_group_v0(env: &Env, group_id: u64) -> Option<GroupV0> {
+    env.storage().persistent().get(&DataKey::Group(group_id))
+}
+
+// ── Davetler ──
+
+/// Grubun bekleyen davetlerini döndürür (davet edilen adres → davet).
+pub fn get_invites(env: &Env, group_id: u64) -> Map<Address, Invite> {
+    let key = DataKey::Invites(group_id);
+    match env.storage().persistent().get(&key) {
+        Some(invites) => {
+            bump_persistent(env, &key);
+            invites
+        }
+        None => Map::new(env),
+    }
+}
+
+/// Bekleyen davetleri kaydeder; boşsa girişi siler.
+pub fn save_invites(env: &Env, group_id: u64, invites: &Map<Address, Invite>) {
+    let key = DataKey::Invites(group_id);
+    if invites.is_empty() {
+        env.storage().persistent().remove(&key);
+        return;
+    }
+    env.storage().persistent().set(&key, invites);
+    bump_persistent(env, &key);
+}
+
+// ── Onay Politikası ──
+
+/// Grubun harcama onay politikasını döndürür (yoksa onay gerekmez).
+pub fn get_approval_policy(env: &Env, group_id: u64) -> Option<ApprovalPolicy> {
+    let key = DataKey::ApprovalPolicy(group_id);
+    let result = env.storage().persistent().get(&key);
+    if result.is_some() {
+        bump_persistent(env, &key);
+    }
+    result
+}
+
+/// Onay politikasını kaydeder; `None` politikayı kaldırır.
+pub fn set_approval_policy(env: &Env, group_id: u64, policy: &Option<ApprovalPolicy>) {
+    let key = DataKey::ApprovalPolicy(group_id);
+    match policy {
+        Some(p) => {
+            env.storage().persistent().set(&key, p);
+            bump_persistent(env, &key);
+        }
+        None => env.storage().persistent().remove(&key),
+    }
+}
+
+/// Bekleyen bir harcamayı onaylamış üyeler.
+pub fn get_expense_approvals(env: &Env, group_id: u64, expense_id: u64) -> Vec<Address> {
+    let key = DataKey::ExpenseApprovals(group_id, expense_id);
+    env.storage().persistent().get(&key).unwrap_or(Vec::new(env))
+}
+
+/// Onay listesini kaydeder; boşsa girişi siler.
+pub fn save_expense_approvals(env: &Env, group_id: u64, expense_id: u64, approvals: &Vec<Address>) {
+    let key = DataKey::ExpenseApprovals(group_id, expense_id);
+    if approvals.is_empty() {
+        env.storage().persistent().remove(&key);
+        return;
+    }
+    env.storage().persistent().set(&key, approvals);
+    bump_persistent(env, &key);
+}
+
+/// Harcamanın makbuz referansı (eklenmemişse `None`).
+pub fn get_receipt(env: &Env, group_id: u64, expense_id: u64) -> Option<Receipt> {
+    env.storage().persistent().get(&DataKey::Receipt(group_id, expense_id))
+}
+
+/// Makbuz referansını kaydeder; `None` girişi siler.
+pub fn set_receipt(env: &Env, group_id: u64, expense_id: u64, receipt: &Option<Receipt>) {
+    let key = DataKey::Receipt(group_id, expense_id);
+    match receipt {
+        Some(r) => {
+            env.storage().persistent().set(&key, r);
+            bump_persistent(env, &key);
+        }
+        None => env.storage().persistent().remove(&key),
+    }
 }
 
 // ── Expense ID Counter ──
@@ -88,29 +257,84 @@ pub fn set_expenses_count(env: &Env, group_id: u64, count: u64) {
 
 // ── Expense CRUD ──
 
-/// Harcamayı kaydeder.
+/// Harcamayı kaydeder ve askıdaki (onay bekleyen veya itirazlı) harcamalar listesini
+/// güncel tutar.
 pub fn save_expense(env: &Env, group_id: u64, expense_id: u64, expense: &Expense) {
     let key = DataKey::Expense(group_id, expense_id);
     env.storage().persistent().set(&key, expense);
     bump_persistent(env, &key);
+
+    let frozen = !expense.deleted && expense.status != ExpenseStatus::Active;
+    let mut ids = get_frozen_expenses(env, group_id);
+    match ids.first_index_of(expense_id) {
+        Some(idx) if !frozen => {
+            ids.remove(idx);
+        }
+        None if frozen => ids.push_back(expense_id),
+        _ => return,
+    }
+    let key = DataKey::FrozenExpenses(group_id);
+    if ids.is_empty() {
+        env.storage().persistent().remove(&key);
+    } else {
+        env.storage().persistent().set(&key, &ids);
+        bump_persistent(env, &key);
+    }
 }
 
-/// Harcamayı okur (yoksa panic).
-pub fn get_expense(env: &Env, group_id: u64, expense_id: u64) -> Expense {
+/// Bakiyelere henüz yansımamış (onay bekleyen veya itirazlı) harcamaların id'leri.
+pub fn get_frozen_expenses(env: &Env, group_id: u64) -> Vec<u64> {
+    env.storage()
+        .persistent()
+        .get(&DataKey::FrozenExpenses(group_id))
+        .unwrap_or(Vec::new(env))
+}
+
+/// Harcamayı okur (yoksa `Error::ExpenseNotFound`).
+pub fn get_expense(env: &Env, group_id: u64, expense_id: u64) -> Result<Expense, Error> {
     let key = DataKey::Expense(group_id, expense_id);
     let expense: Expense = env
         .storage()
         .persistent()
         .get(&key)
-        .expect("expense not found");
+        .ok_or(Error::ExpenseNotFound)?;
     bump_persistent(env, &key);
-    expense
+    Ok(expense)
 }
 
-/// Son eklenen harcamayı siler (sadece cancel_last_expense tarafından kullanılır).
-pub fn remove_expense(env: &Env, group_id: u64, expense_id: u64) {
-    let key = DataKey::Expense(group_id, expense_id);
-    env.storage().persistent().remove(&key);
+// ── Payments ──
+
+/// Bir sonraki payment ID'yi döndürür.
+pub fn get_next_payment_id(env: &Env, group_id: u64) -> u64 {
+    let key = DataKey::NextPaymentId(group_id);
+    env.storage().persistent().get(&key).unwrap_or(0)
+    // TTL bump yok — key henüz yaratılmamış olabilir.
+}
+
+/// Bir sonraki payment ID'yi kaydeder.
+pub fn set_next_payment_id(env: &Env, group_id: u64, id: u64) {
+    let key = DataKey::NextPaymentId(group_id);
+    env.storage().persistent().set(&key, &id);
+    bump_persistent(env, &key);
+}
+
+/// Ödemeyi kaydeder.
+pub fn save_payment(env: &Env, group_id: u64, payment: &Payment) {
+    let key = DataKey::Payment(group_id, payment.id);
+    env.storage().persistent().set(&key, payment);
+    bump_persistent(env, &key);
+}
+
+/// Ödemeyi okur (yoksa `Error::PaymentNotFound`).
+pub fn get_payment(env: &Env, group_id: u64, payment_id: u64) -> Result<Payment, Error> {
+    let key = DataKey::Payment(group_id, payment_id);
+    let payment: Payment = env
+        .storage()
+        .persistent()
+        .get(&key)
+        .ok_or(Error::PaymentNotFound)?;
+    bump_persistent(env, &key);
+    Ok(payment)
 }
 
 // ── Settlement Status ──
@@ -129,6 +353,94 @@ pub fn set_group_settled(env: &Env, group_id: u64, settled: bool) {
     bump_persistent(env, &key);
 }
 
+// ── Net Bakiye Önbelleği ──
+
+/// Grubun bir token'daki önbelleğe alınmış net bakiyelerini döndürür (hiç yazılmamışsa None).
+pub fn get_group_balances(env: &Env, group_id: u64, token: &Address) -> Option<Map<Address, i128>> {
+    let key = DataKey::Balances(group_id, token.clone());
+    let result = env.storage().persistent().get(&key);
+    if result.is_some() {
+        bump_persistent(env, &key);
+    }
+    result
+}
+
+/// Grubun bir token'daki net bakiye önbelleğini kaydeder.
+pub fn save_group_balances(env: &Env, group_id: u64, token: &Address, balances: &Map<Address, i128>) {
+    let key = DataKey::Balances(group_id, token.clone());
+    env.storage().persistent().set(&key, balances);
+    bump_persistent(env, &key);
+}
+
+// ── Kategori Kaydı ──
+
+/// Grubun kategori kaydını döndürür (hiç düzenlenmemişse None; varsayılanlar geçerlidir).
+pub fn get_categories(env: &Env, group_id: u64) -> Option<Map<String, String>> {
+    let key = DataKey::Categories(group_id);
+    let result = env.storage().persistent().get(&key);
+    if result.is_some() {
+        bump_persistent(env, &key);
+    }
+    result
+}
+
+/// Grubun kategori kaydını kaydeder.
+pub fn save_categories(env: &Env, group_id: u64, categories: &Map<String, String>) {
+    let key = DataKey::Categories(group_id);
+    env.storage().persistent().set(&key, categories);
+    bump_persistent(env, &key);
+}
+
+// ── Tekrarlayan Harcamalar ──
+
+/// Grubun tekrarlayan harcama şablonları (id -> şablon).
+pub fn get_recurring(env: &Env, group_id: u64) -> Map<u64, RecurringExpense> {
+    let key = DataKey::Recurring(group_id);
+    env.storage().persistent().get(&key).unwrap_or(Map::new(env))
+}
+
+/// Şablonları kaydeder; boşsa girişi siler.
+pub fn save_recurring(env: &Env, group_id: u64, templates: &Map<u64, RecurringExpense>) {
+    let key = DataKey::Recurring(group_id);
+    if templates.is_empty() {
+        env.storage().persistent().remove(&key);
+        return;
+    }
+    env.storage().persistent().set(&key, templates);
+    bump_persistent(env, &key);
+}
+
+/// Bir sonraki şablon id'sini döndürür.
+pub fn get_next_recurring_id(env: &Env, group_id: u64) -> u64 {
+    let key = DataKey::NextRecurringId(group_id);
+    env.storage().persistent().get(&key).unwrap_or(0)
+}
+
+pub fn set_next_recurring_id(env: &Env, group_id: u64, id: u64) {
+    let key = DataKey::NextRecurringId(group_id);
+    env.storage().persistent().set(&key, &id);
+    bump_persistent(env, &key);
+}
+
+// ── Harcama Özeti Önbelleği ──
+
+/// Grubun bir token'daki harcama özetini döndürür (hiç yazılmamışsa None).
+pub fn get_expense_summary(env: &Env, group_id: u64, token: &Address) -> Option<ExpenseSummary> {
+    let key = DataKey::Summary(group_id, token.clone());
+    let result = env.storage().persistent().get(&key);
+    if result.is_some() {
+        bump_persistent(env, &key);
+    }
+    result
+}
+
+/// Grubun bir token'daki harcama özetini kaydeder.
+pub fn save_expense_summary(env: &Env, group_id: u64, token: &Address, summary: &ExpenseSummary) {
+    let key = DataKey::Summary(group_id, token.clone());
+    env.storage().persistent().set(&key, summary);
+    bump_persistent(env, &key);
+}
+
 // ── Guardian & Recovery ──
 
 pub fn save_guardian_config(env: &Env, user: &Address, config: &GuardianConfig) {
@@ -0,0 +1,62 @@
+use soroban_sdk::{Address, Env, Map};
+
+use crate::errors::Error;
+use crate::split::compute_owed;
+use crate::types::{CategoryTotals, Expense, ExpenseSummary, MemberTotals};
+
+/// Boş bir harcama özeti.
+pub fn empty_summary(env: &Env) -> ExpenseSummary {
+    ExpenseSummary {
+        members: Map::new(env),
+        categories: Map::new(env),
+    }
+}
+
+/// Bir harcamanın etkisini özete uygular (`sign = 1` ekler, `-1` geri alır).
+///
+/// Her payer'ın `paid` toplamı katkısı kadar, her borçlunun `owed` toplamı
+/// kendi payı (kalan dahil) kadar değişir; aynısı harcamanın kategorisine de
+/// yazılır. Sıfıra inen girişler silinir, böylece özet yeniden oynatmayla
+/// birebir karşılaştırılabilir.
+pub fn apply_summary(
+    env: &Env,
+    summary: &mut ExpenseSummary,
+    expense: &Expense,
+    sign: i128,
+) -> Result<(), Error> {
+    let owed = compute_owed(env, expense)?;
+
+    let mut category = summary
+        .categories
+        .get(expense.category.clone())
+        .unwrap_or(CategoryTotals { total: 0, members: Map::new(env) });
+    category.total += sign * expense.amount;
+
+    for members in [&mut summary.members, &mut category.members] {
+        for (payer, paid) in expense.contributions.iter() {
+            add_totals(members, &payer, sign * paid, 0);
+        }
+        for (member, part) in owed.iter() {
+            add_totals(members, &member, 0, sign * part);
+        }
+    }
+
+    if category.total == 0 && category.members.is_empty() {
+        summary.categories.remove(expense.category.clone());
+    } else {
+        summary.categories.set(expense.category.clone(), category);
+    }
+    Ok(())
+}
+
+/// Bir üyenin toplamlarına ekler; iki toplam da sıfırlanırsa girişi siler.
+fn add_totals(members: &mut Map<Address, MemberTotals>, member: &Address, paid: i128, owed: i128) {
+    let mut totals = members.get(member.clone()).unwrap_or_default();
+    totals.paid += paid;
+    totals.owed += owed;
+    if totals == MemberTotals::default() {
+        members.remove(member.clone());
+    } else {
+        members.set(member.clone(), totals);
+    }
+}
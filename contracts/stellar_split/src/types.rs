@@ -1,4 +1,4 @@
-use soroban_sdk::{contracttype, Address, String, Vec};
+use soroban_sdk::{contracttype, Address, BytesN, Map, String, Vec};
 
 /// Bir gruptaki üyeleri ve harcama sayısını tutar.
 #[contracttype]
@@ -7,21 +7,254 @@ pub struct Group {
     pub id: u64,
     pub name: String,
     pub members: Vec<Address>,
+    /// Grubun sahibi: adminleri atar, sahipliği devredebilir; her zaman admindir.
+    pub owner: Address,
+    /// Üyelik yönetebilen, her harcamayı düzenleyip silebilen ve settle başlatabilen üyeler.
+    pub admins: Vec<Address>,
+    /// Ana token: vault, kumbara ve token belirtilmeyen harcamalar bunu kullanır.
     pub token: Address,
+    /// Harcamalarda kullanılabilecek token'lar (ana token daima ilk sırada).
+    pub tokens: Vec<Address>,
     pub expense_count: u64,
+    /// Bölünemeyen kalan stroops'un kime yazılacağı (yeni harcamalara uygulanır).
+    pub rounding: RoundingPolicy,
+}
+
+/// Bir üyenin gruptaki rolü.
+#[contracttype]
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+#[repr(u32)]
+pub enum GroupRole {
+    /// Sadece kendi harcamalarını ekleyip düzenleyebilir.
+    Member = 0,
+    /// Üyelik yönetimi, tüm harcamaları düzenleme ve settle.
+    Admin = 1,
+    /// Admin yetkilerine ek olarak admin atama ve sahiplik devri.
+    Owner = 2,
+}
+
+/// Gruba katılma daveti. Davet edilen kişi `accept_invite` ile kendi imzasıyla
+/// kabul edene kadar üye sayılmaz ve harcamalarda kullanılamaz.
+#[contracttype]
+#[derive(Clone, Debug, PartialEq)]
+pub struct Invite {
+    pub invitee: Address,
+    pub inviter: Address,
+    /// Ledger zaman damgası (saniye); bu andan sonra kabul edilemez.
+    pub expires_at: u64,
+}
+
+/// Harcamanın `split_among` arasında nasıl bölüneceği.
+#[contracttype]
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+#[repr(u32)]
+pub enum SplitMode {
+    /// Herkes eşit pay öder; `split_values` boş olmalı.
+    Equal = 0,
+    /// Baz puan ağırlıkları (10_000 = %100); toplamı tam 10_000 olmalı.
+    Percentage = 1,
+    /// Kişi başı kesin tutarlar (stroops); toplamı `amount`'a eşit olmalı.
+    Exact = 2,
+    /// Pay adetleri (ör. 2:1:1); tutar pay oranında bölünür.
+    Shares = 3,
+}
+
+/// Eşit/ağırlıklı bölüşümde tam bölünemeyen kalanın (stroops) kime yazılacağı.
+/// Kalanın tamamı tek bir kişinin payına eklenir; böylece bakiyeler daima sıfıra toplanır.
+#[contracttype]
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+#[repr(u32)]
+pub enum RoundingPolicy {
+    /// Kalan ödeyen kişinin payına eklenir.
+    Payer = 0,
+    /// Kalan `split_among` listesindeki ilk kişiye eklenir.
+    FirstMember = 1,
+    /// Kalan `split_among[expense_id % n]` kişisine eklenir (harcamalar arasında döner).
+    Rotate = 2,
 }
 
 /// Tek bir harcamayı temsil eder.
 #[contracttype]
 #[derive(Clone, Debug, PartialEq)]
 pub struct Expense {
+    pub id: u64,
+    /// Birincil payer: harcamayı düzenleyip silebilir, `Payer` yuvarlamasında kalanı alır.
+    pub payer: Address,
+    pub amount: i128,
+    /// Harcamanın para birimi; grubun izin listesinden biri.
+    pub token: Address,
+    pub split_among: Vec<Address>,
+    pub description: String,
+    /// Grubun kategori kaydındaki kategori id'si (ör. `food`); boş = kategorisiz.
+    /// Görünen ad kayıttan okunur, bu yüzden yeniden adlandırma harcamaları değiştirmez.
+    pub category: String,
+    pub split_mode: SplitMode,
+    /// `split_among` ile aynı sırada, moda göre yorumlanan değerler (Equal için boş).
+    pub split_values: Vec<i128>,
+    /// Harcama eklendiği andaki grup yuvarlama politikası.
+    pub rounding: RoundingPolicy,
+    /// Silinmiş (tombstone) harcama: id korunur, bakiyelere katılmaz.
+    pub deleted: bool,
+    /// Sadece `Active` harcamalar bakiyelere katılır.
+    pub status: ExpenseStatus,
+    /// Ödeyenler ve katkıları (toplamı `amount`); tek payer'lı harcamada `{payer: amount}`.
+    pub contributions: Map<Address, i128>,
+    /// Harcamayı kaydeden adres; tekrarlayan şablondan oluşanlarda kontratın kendisi.
+    pub submitter: Address,
+    /// Oluşturulduğu ledger.
+    pub created: LedgerStamp,
+    /// Kaydın son değiştiği ledger (oluşturulmada `created` ile aynı).
+    pub modified: LedgerStamp,
+    /// Kullanıcının bildirdiği harcama zamanı (Unix saniye); `None` = bildirilmedi.
+    pub occurred_at: Option<u64>,
+}
+
+/// Harcama makbuzu: zincir dışı dosyanın SHA-256 özeti ve adresi (URI veya IPFS CID).
+#[contracttype]
+#[derive(Clone, Debug, PartialEq)]
+pub struct Receipt {
+    pub hash: BytesN<32>,
+    pub uri: String,
+}
+
+/// Bir yazımın ledger zamanı ve sıra numarası.
+#[contracttype]
+#[derive(Clone, Debug, PartialEq)]
+pub struct LedgerStamp {
+    pub timestamp: u64,
+    pub sequence: u32,
+}
+
+/// Tekrarlayan harcama şablonu (kira, internet, faturalar...).
+/// `materialize_recurring` vadesi gelen her dönem için bir `Expense` oluşturur.
+#[contracttype]
+#[derive(Clone, Debug, PartialEq)]
+pub struct RecurringExpense {
     pub id: u64,
     pub payer: Address,
     pub amount: i128,
+    pub token: Address,
     pub split_among: Vec<Address>,
+    pub split_mode: SplitMode,
+    pub split_values: Vec<i128>,
+    pub description: String,
+    pub category: String,
+    pub schedule: RecurringSchedule,
+    /// Oluşturulmuş dönem sayısı; sıradaki vade `start + materialized * period`.
+    pub materialized: u32,
+}
+
+/// Tekrarlayan harcamanın zamanlaması (ledger zaman damgası, saniye).
+#[contracttype]
+#[derive(Clone, Debug, PartialEq)]
+pub struct RecurringSchedule {
+    /// Dönem uzunluğu; sıfırdan büyük olmalı.
+    pub period: u64,
+    /// İlk dönemin vadesi.
+    pub start: u64,
+    /// Bu zamandan sonra vadesi gelen dönem oluşturulmaz (dahil); `None` = süresiz.
+    pub end: Option<u64>,
+}
+
+/// Harcamanın onay / itiraz durumu.
+#[contracttype]
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+#[repr(u32)]
+pub enum ExpenseStatus {
+    /// Bakiyelere katılır.
+    Active = 0,
+    /// Onay eşiğini aştı; etkilenen üyelerin onayını bekliyor.
+    Pending = 1,
+    /// Bir borçlu itiraz etti; payer veya admin çözene kadar bakiyelere katılmaz.
+    Disputed = 2,
+}
+
+/// Onay gerektiren harcamanın ne zaman aktifleşeceği.
+#[contracttype]
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+#[repr(u32)]
+pub enum ApprovalMode {
+    /// Payer dışındaki tüm borçlular onaylamalı.
+    All = 0,
+    /// Payer dışındaki borçluların yarısından fazlası onaylamalı.
+    Majority = 1,
+}
+
+/// Grup onay politikası: tutarı `threshold`'u aşan harcamalar onaya düşer.
+/// Eşik token'dan bağımsız ham tutardır.
+#[contracttype]
+#[derive(Clone, Debug, PartialEq)]
+pub struct ApprovalPolicy {
+    pub threshold: i128,
+    pub mode: ApprovalMode,
+}
+
+/// Yeni harcamanın serbest alanları (`add_split_expense`, `add_multi_payer_expense`).
+#[contracttype]
+#[derive(Clone, Debug, PartialEq)]
+pub struct ExpenseDetails {
     pub description: String,
-    /// Kategori: yemek, ulasim, konaklama, eglence, market, diger vb.
+    /// Kayıtlı kategori id'si veya boş (kategorisiz).
     pub category: String,
+    /// Kullanıcının bildirdiği harcama zamanı (Unix saniye); bkz. `Expense::occurred_at`.
+    pub occurred_at: Option<u64>,
+}
+
+/// `query_expenses` filtresi; `None` alanlar filtrelenmez, dolu alanlar birlikte (VE) uygulanır.
+/// Zaman aralıkları dahildir.
+#[contracttype]
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct ExpenseFilter {
+    /// Sadece bu adresin ödediği harcamalar.
+    pub payer: Option<Address>,
+    /// Bu adresin payer veya borçlu olarak yer aldığı harcamalar.
+    pub member: Option<Address>,
+    /// Kategorisi tam olarak eşleşen harcamalar.
+    pub category: Option<String>,
+    /// Bu adresin kaydettiği harcamalar.
+    pub submitter: Option<Address>,
+    /// Oluşturulma zamanı (`created.timestamp`) alt / üst sınırı.
+    pub created_from: Option<u64>,
+    pub created_to: Option<u64>,
+    /// Bildirilen harcama zamanı alt / üst sınırı; `occurred_at` boş olanlar elenir.
+    pub occurred_from: Option<u64>,
+    pub occurred_to: Option<u64>,
+}
+
+/// Bir üyenin harcamalardaki toplamları: ödediği ve payına düşen tutar.
+#[contracttype]
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct MemberTotals {
+    pub paid: i128,
+    pub owed: i128,
+}
+
+/// Bir kategorideki toplam harcama ve üye bazında dağılımı.
+#[contracttype]
+#[derive(Clone, Debug, PartialEq)]
+pub struct CategoryTotals {
+    pub total: i128,
+    pub members: Map<Address, MemberTotals>,
+}
+
+/// Grubun tek bir token'daki aktif harcamalarının özeti.
+/// Harcama yazımlarıyla birlikte artımlı güncellenir.
+#[contracttype]
+#[derive(Clone, Debug, PartialEq)]
+pub struct ExpenseSummary {
+    pub members: Map<Address, MemberTotals>,
+    pub categories: Map<String, CategoryTotals>,
+}
+
+/// Harcama listesinin bir sayfası (id sırasıyla, silinmişler hariç).
+#[contracttype]
+#[derive(Clone, Debug, PartialEq)]
+pub struct ExpensePage {
+    pub expenses: Vec<Expense>,
+    /// Sayfadaki harcamaların makbuzları (harcama id → makbuz); makbuzu olmayanlar yer almaz.
+    pub receipts: Map<u64, Receipt>,
+    /// Sonraki sayfa için `start` değeri; kayıt sonuna gelindiyse `None`.
+    pub next: Option<u64>,
 }
 
 /// Bir uzlaşma transferini temsil eder.
@@ -31,6 +264,73 @@ pub struct Settlement {
     pub from: Address,
     pub to: Address,
     pub amount: i128,
+    pub token: Address,
+}
+
+/// Hesaplanmış bir uzlaşma planı.
+/// `id`, planın hesaplandığı grup durumunun SHA-256 özetidir; `settle_group`
+/// yalnızca durum değişmemişse (aynı id) planı yürütür.
+#[contracttype]
+#[derive(Clone, Debug, PartialEq)]
+pub struct SettlementPlan {
+    pub id: BytesN<32>,
+    pub solver: SettlementSolver,
+    pub settlements: Vec<Settlement>,
+}
+
+/// Uzlaşma planını üreten algoritma.
+#[contracttype]
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+#[repr(u32)]
+pub enum SettlementSolver {
+    /// En büyük borçlu ile en büyük alacaklıyı eşleştirir; en fazla N-1 transfer.
+    Greedy = 0,
+    /// Kesin minimum transfer sayısı (sıfır toplamlı alt küme bölümlemesi).
+    /// Sıfırdan farklı bakiyeli kişi sayısı sınırı aşarsa Greedy'e düşer.
+    Exact = 1,
+}
+
+/// Uzlaşma planı dışında, iki üye arasında yapılmış (kısmi) bir geri ödeme.
+#[contracttype]
+#[derive(Clone, Debug, PartialEq)]
+pub struct Payment {
+    pub id: u64,
+    pub from: Address,
+    pub to: Address,
+    pub amount: i128,
+    pub token: Address,
+}
+
+/// Settle ödülünün kime verileceği.
+#[contracttype]
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+#[repr(u32)]
+pub enum RewardRecipients {
+    /// Sadece `settle_group` çağıran kişi.
+    Settler = 0,
+    /// Uzlaşma planında transfer yapan her borçlu.
+    Debtors = 1,
+    /// Settle'dan önce `record_payment` ile ödeme yapanlar, ödeme sırasına göre.
+    FastestPayers = 2,
+}
+
+/// Kontrat genelindeki settle ödülü yapılandırması (admin tarafından ayarlanır).
+///
+/// Her alıcıya düşen ödül:
+/// `base_amount + per_transfer * transfer_sayısı + hacim * volume_bps / 10_000`.
+/// Transfer sayısı ve hacim alıcıya göre hesaplanır (settler için tüm plan);
+/// hacme yalnızca ödül token'ındaki transferler katılır.
+#[contracttype]
+#[derive(Clone, Debug, PartialEq)]
+pub struct RewardConfig {
+    /// Mint edilecek ödül token'ı (SPLT veya admin'i bu kontrat olan bir SAC).
+    pub token: Address,
+    pub recipients: RewardRecipients,
+    pub base_amount: i128,
+    pub per_transfer: i128,
+    pub volume_bps: u32,
+    /// Ödül alacak en fazla kişi sayısı; 0 = sınırsız.
+    pub max_recipients: u32,
 }
 
 /// Vasi (Guardian) yapılandırması.
@@ -100,4 +400,57 @@ pub enum DataKey {
     GroupVault(u64),        // Sadece bir gruba ait Vault
     UserBadges(Address),    // Kullanıcının rozetleri
     SavingsPool(u64),       // group_id -> SavingsPool
+    Balances(u64, Address), // (group_id, token) -> Map<Address, i128> (net bakiye önbelleği)
+    NextPaymentId(u64),     // group_id -> sonraki payment id
+    Payment(u64, u64),      // (group_id, payment_id)
+    Admin,                  // Kontrat yöneticisi (instance)
+    PendingAdmin,           // Devri kabul bekleyen yeni yönetici (instance)
+    StorageVersion,         // Depolama şeması sürümü (instance)
+    Invites(u64),           // group_id -> Map<Address, Invite> (bekleyen davetler)
+    ApprovalPolicy(u64),    // group_id -> ApprovalPolicy
+    ExpenseApprovals(u64, u64), // (group_id, expense_id) -> Vec<Address> (bekleyen onaylar)
+    Summary(u64, Address),  // (group_id, token) -> ExpenseSummary (harcama özeti önbelleği)
+    Categories(u64),        // group_id -> Map<String, String> (kategori id -> görünen ad)
+    Recurring(u64),         // group_id -> Map<u64, RecurringExpense> (tekrarlayan harcama şablonları)
+    NextRecurringId(u64),   // group_id -> sonraki şablon id
+    Receipt(u64, u64),      // (group_id, expense_id) -> Receipt (makbuz referansı)
+    RewardConfig,           // Settle ödülü yapılandırması (instance)
+    MigrationCursor,        // Yarım kalmış `migrate`in devam konumu (instance)
+    FrozenExpenses(u64),    // group_id -> Vec<u64> (onay bekleyen veya itirazlı harcamalar)
+}
+
+/// Partiler halinde ilerleyen `migrate`in devam konumu: sıradaki grup ve
+/// o grupta sıradaki harcama id'si.
+#[contracttype]
+#[derive(Clone, Debug, PartialEq)]
+pub struct MigrationCursor {
+    pub group_id: u64,
+    pub expense_id: u64,
+}
+
+// ── Eski Şema Sürümleri ──
+// Sadece `migrate` adımlarında eski kayıtları okumak için; spec'e dahil edilmez.
+
+/// Depolama v0'daki (ilk sürüm) `Group` düzeni: admin, token listesi ve yuvarlama yok.
+#[contracttype(export = false)]
+#[derive(Clone, Debug, PartialEq)]
+pub struct GroupV0 {
+    pub id: u64,
+    pub name: String,
+    pub members: Vec<Address>,
+    pub token: Address,
+    pub expense_count: u64,
+}
+
+/// Depolama v0'daki (ilk sürüm) `Expense` düzeni: token ve bölüşüm modu yok,
+/// tutar `split_among` arasında eşit bölünür.
+#[contracttype(export = false)]
+#[derive(Clone, Debug, PartialEq)]
+pub struct ExpenseV0 {
+    pub id: u64,
+    pub payer: Address,
+    pub amount: i128,
+    pub split_among: Vec<Address>,
+    pub description: String,
+    pub category: String,
 }
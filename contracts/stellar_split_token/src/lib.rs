@@ -3,7 +3,25 @@
 #[cfg(test)]
 mod test;
 
-use soroban_sdk::{contract, contractimpl, Address, Env, String, Symbol};
+use soroban_sdk::token::TokenInterface;
+use soroban_sdk::{contract, contractimpl, contracttype, Address, Env, String, Symbol};
+
+// ── TTL Sabitleri ──
+// Instance (admin/metadata) ve bakiye girişleri için, ana kontrattaki değerlerle aynı.
+const LIFETIME_THRESHOLD: u32 = 17_280; // ~1 gün (5 saniye/ledger)
+const BUMP_AMOUNT: u32 = 518_400;       // ~30 gün
+
+/// SPLT ondalık basamak sayısı (Stellar varlıklarıyla aynı: 7).
+const DECIMALS: u32 = 7;
+
+/// Bir harcayıcıya (spender) verilmiş harcama izni.
+/// `expiration_ledger` geçtikten sonra izin 0 kabul edilir.
+#[contracttype]
+#[derive(Clone, Debug, PartialEq)]
+pub struct AllowanceValue {
+    pub amount: i128,
+    pub expiration_ledger: u32,
+}
 
 #[contract]
 pub struct StellarSplitToken;
@@ -17,24 +35,206 @@ impl StellarSplitToken {
         env.storage().instance().set(&Symbol::new(&env, "admin"), &admin);
         env.storage().instance().set(&Symbol::new(&env, "name"), &name);
         env.storage().instance().set(&Symbol::new(&env, "symbol"), &symbol);
+        bump_instance(&env);
     }
 
     pub fn mint(env: Env, to: Address, amount: i128) {
+        check_nonnegative_amount(amount);
         let admin: Address = env.storage().instance().get(&Symbol::new(&env, "admin")).unwrap();
         admin.require_auth();
+        bump_instance(&env);
+
+        receive_balance(&env, &to, amount);
+
+        // SEP-41: topics = ("mint", admin, to), data = amount
+        env.events().publish(
+            (Symbol::new(&env, "mint"), admin, to),
+            amount,
+        );
+    }
+}
+
+#[contractimpl]
+impl TokenInterface for StellarSplitToken {
+    fn allowance(env: Env, from: Address, spender: Address) -> i128 {
+        bump_instance(&env);
+        read_allowance(&env, &from, &spender).amount
+    }
+
+    fn approve(env: Env, from: Address, spender: Address, amount: i128, expiration_ledger: u32) {
+        from.require_auth();
+        check_nonnegative_amount(amount);
+        bump_instance(&env);
+
+        write_allowance(&env, &from, &spender, amount, expiration_ledger);
+
+        // SEP-41: topics = ("approve", from, spender), data = (amount, expiration_ledger)
+        env.events().publish(
+            (Symbol::new(&env, "approve"), from, spender),
+            (amount, expiration_ledger),
+        );
+    }
+
+    fn balance(env: Env, id: Address) -> i128 {
+        bump_instance(&env);
+        read_balance(&env, &id)
+    }
+
+    fn transfer(env: Env, from: Address, to: Address, amount: i128) {
+        from.require_auth();
+        check_nonnegative_amount(amount);
+        bump_instance(&env);
+
+        spend_balance(&env, &from, amount);
+        receive_balance(&env, &to, amount);
+
+        env.events().publish(
+            (Symbol::new(&env, "transfer"), from, to),
+            amount,
+        );
+    }
+
+    fn transfer_from(env: Env, spender: Address, from: Address, to: Address, amount: i128) {
+        spender.require_auth();
+        check_nonnegative_amount(amount);
+        bump_instance(&env);
+
+        spend_allowance(&env, &from, &spender, amount);
+        spend_balance(&env, &from, amount);
+        receive_balance(&env, &to, amount);
+
+        env.events().publish(
+            (Symbol::new(&env, "transfer"), from, to),
+            amount,
+        );
+    }
+
+    fn burn(env: Env, from: Address, amount: i128) {
+        from.require_auth();
+        check_nonnegative_amount(amount);
+        bump_instance(&env);
+
+        spend_balance(&env, &from, amount);
+
+        env.events().publish(
+            (Symbol::new(&env, "burn"), from),
+            amount,
+        );
+    }
 
-        let balance_key = (Symbol::new(&env, "balance"), to.clone());
-        let balance: i128 = env.storage().persistent().get(&balance_key).unwrap_or(0);
-        env.storage().persistent().set(&balance_key, &(balance + amount));
+    fn burn_from(env: Env, spender: Address, from: Address, amount: i128) {
+        spender.require_auth();
+        check_nonnegative_amount(amount);
+        bump_instance(&env);
+
+        spend_allowance(&env, &from, &spender, amount);
+        spend_balance(&env, &from, amount);
 
         env.events().publish(
-            (Symbol::new(&env, "mint"), to),
+            (Symbol::new(&env, "burn"), from),
             amount,
         );
     }
 
-    pub fn balance(env: Env, user: Address) -> i128 {
-        let balance_key = (Symbol::new(&env, "balance"), user);
-        env.storage().persistent().get(&balance_key).unwrap_or(0)
+    fn decimals(_env: Env) -> u32 {
+        DECIMALS
+    }
+
+    fn name(env: Env) -> String {
+        bump_instance(&env);
+        env.storage().instance().get(&Symbol::new(&env, "name")).unwrap()
+    }
+
+    fn symbol(env: Env) -> String {
+        bump_instance(&env);
+        env.storage().instance().get(&Symbol::new(&env, "symbol")).unwrap()
+    }
+}
+
+// ── Yardımcılar ──
+
+fn check_nonnegative_amount(amount: i128) {
+    if amount < 0 {
+        panic!("negative amount is not allowed");
+    }
+}
+
+fn bump_instance(env: &Env) {
+    env.storage().instance().extend_ttl(LIFETIME_THRESHOLD, BUMP_AMOUNT);
+}
+
+// ── Bakiye ──
+
+fn read_balance(env: &Env, id: &Address) -> i128 {
+    let key = (Symbol::new(env, "balance"), id.clone());
+    match env.storage().persistent().get::<_, i128>(&key) {
+        Some(balance) => {
+            env.storage().persistent().extend_ttl(&key, LIFETIME_THRESHOLD, BUMP_AMOUNT);
+            balance
+        }
+        None => 0,
+    }
+}
+
+fn write_balance(env: &Env, id: &Address, amount: i128) {
+    let key = (Symbol::new(env, "balance"), id.clone());
+    env.storage().persistent().set(&key, &amount);
+    env.storage().persistent().extend_ttl(&key, LIFETIME_THRESHOLD, BUMP_AMOUNT);
+}
+
+fn receive_balance(env: &Env, id: &Address, amount: i128) {
+    let balance = read_balance(env, id);
+    write_balance(env, id, balance.checked_add(amount).expect("balance overflow"));
+}
+
+fn spend_balance(env: &Env, id: &Address, amount: i128) {
+    let balance = read_balance(env, id);
+    if balance < amount {
+        panic!("insufficient balance");
+    }
+    write_balance(env, id, balance - amount);
+}
+
+// ── Harcama İzni (Allowance) ──
+// Temporary storage'da tutulur; TTL son kullanma ledger'ına kadar uzatılır.
+
+fn read_allowance(env: &Env, from: &Address, spender: &Address) -> AllowanceValue {
+    let key = (Symbol::new(env, "allowance"), from.clone(), spender.clone());
+    match env.storage().temporary().get::<_, AllowanceValue>(&key) {
+        Some(allowance) if allowance.expiration_ledger >= env.ledger().sequence() => allowance,
+        Some(allowance) => AllowanceValue {
+            amount: 0,
+            expiration_ledger: allowance.expiration_ledger,
+        },
+        None => AllowanceValue {
+            amount: 0,
+            expiration_ledger: 0,
+        },
+    }
+}
+
+fn write_allowance(env: &Env, from: &Address, spender: &Address, amount: i128, expiration_ledger: u32) {
+    let ledger = env.ledger().sequence();
+    if amount > 0 && expiration_ledger < ledger {
+        panic!("expiration_ledger is less than ledger seq when amount > 0");
+    }
+
+    let key = (Symbol::new(env, "allowance"), from.clone(), spender.clone());
+    let allowance = AllowanceValue { amount, expiration_ledger };
+    env.storage().temporary().set(&key, &allowance);
+
+    if amount > 0 {
+        let live_for = expiration_ledger - ledger;
+        env.storage().temporary().extend_ttl(&key, live_for, live_for);
+    }
+}
+
+fn spend_allowance(env: &Env, from: &Address, spender: &Address, amount: i128) {
+    let allowance = read_allowance(env, from, spender);
+    if allowance.amount < amount {
+        panic!("insufficient allowance");
+    }
+    if amount > 0 {
+        write_allowance(env, from, spender, allowance.amount - amount, allowance.expiration_ledger);
     }
 }
@@ -1,7 +1,7 @@
 #![cfg(test)]
 
 use super::*;
-use soroban_sdk::testutils::{Address as _, Events};
+use soroban_sdk::testutils::{Address as _, Events, Ledger};
 use soroban_sdk::{Env, IntoVal, String, Symbol};
 
 fn setup() -> (Env, StellarSplitTokenClient<'static>, Address) {
@@ -120,3 +120,207 @@ fn test_mint_emits_event() {
     let topic_symbol: Symbol = last.1.get(0).unwrap().into_val(&env);
     assert_eq!(topic_symbol, Symbol::new(&env, "mint"));
 }
+
+// ═══════════════════════════════════════════════════
+//  METADATA TESTLERİ
+// ═══════════════════════════════════════════════════
+
+#[test]
+fn test_metadata_getters() {
+    let (env, client, admin) = setup();
+    client.initialize(&admin, &String::from_str(&env, "StellarSplit"), &String::from_str(&env, "SPLT"));
+
+    assert_eq!(client.name(), String::from_str(&env, "StellarSplit"));
+    assert_eq!(client.symbol(), String::from_str(&env, "SPLT"));
+    assert_eq!(client.decimals(), 7);
+}
+
+// ═══════════════════════════════════════════════════
+//  TRANSFER TESTLERİ
+// ═══════════════════════════════════════════════════
+
+#[test]
+fn test_transfer_moves_balance() {
+    let (env, client, admin) = setup();
+    client.initialize(&admin, &String::from_str(&env, "Token"), &String::from_str(&env, "TKN"));
+
+    let alice = Address::generate(&env);
+    let bob = Address::generate(&env);
+    client.mint(&alice, &1000);
+    client.transfer(&alice, &bob, &400);
+
+    // SEP-41: ("transfer", from, to) → amount
+    let last = env.events().all().last().unwrap();
+    let topic_symbol: Symbol = last.1.get(0).unwrap().into_val(&env);
+    let from: Address = last.1.get(1).unwrap().into_val(&env);
+    let to: Address = last.1.get(2).unwrap().into_val(&env);
+    let amount: i128 = last.2.into_val(&env);
+    assert_eq!(topic_symbol, Symbol::new(&env, "transfer"));
+    assert_eq!(from, alice);
+    assert_eq!(to, bob);
+    assert_eq!(amount, 400);
+
+    assert_eq!(client.balance(&alice), 600);
+    assert_eq!(client.balance(&bob), 400);
+}
+
+#[test]
+#[should_panic(expected = "insufficient balance")]
+fn test_transfer_insufficient_balance_panics() {
+    let (env, client, admin) = setup();
+    client.initialize(&admin, &String::from_str(&env, "Token"), &String::from_str(&env, "TKN"));
+
+    let alice = Address::generate(&env);
+    let bob = Address::generate(&env);
+    client.mint(&alice, &100);
+    client.transfer(&alice, &bob, &101);
+}
+
+#[test]
+#[should_panic(expected = "negative amount is not allowed")]
+fn test_transfer_negative_amount_panics() {
+    let (env, client, admin) = setup();
+    client.initialize(&admin, &String::from_str(&env, "Token"), &String::from_str(&env, "TKN"));
+
+    let alice = Address::generate(&env);
+    let bob = Address::generate(&env);
+    client.mint(&alice, &100);
+    client.transfer(&alice, &bob, &-1);
+}
+
+// ═══════════════════════════════════════════════════
+//  ALLOWANCE TESTLERİ
+// ═══════════════════════════════════════════════════
+
+#[test]
+fn test_approve_and_transfer_from() {
+    let (env, client, admin) = setup();
+    client.initialize(&admin, &String::from_str(&env, "Token"), &String::from_str(&env, "TKN"));
+
+    let alice = Address::generate(&env);
+    let spender = Address::generate(&env);
+    let bob = Address::generate(&env);
+    client.mint(&alice, &1000);
+
+    client.approve(&alice, &spender, &300, &200);
+
+    // SEP-41: ("approve", from, spender) → (amount, expiration_ledger)
+    let last = env.events().all().last().unwrap();
+    let topic_symbol: Symbol = last.1.get(0).unwrap().into_val(&env);
+    let data: (i128, u32) = last.2.into_val(&env);
+    assert_eq!(topic_symbol, Symbol::new(&env, "approve"));
+    assert_eq!(data, (300, 200));
+    assert_eq!(client.allowance(&alice, &spender), 300);
+
+    client.transfer_from(&spender, &alice, &bob, &120);
+    assert_eq!(client.allowance(&alice, &spender), 180);
+    assert_eq!(client.balance(&alice), 880);
+    assert_eq!(client.balance(&bob), 120);
+}
+
+#[test]
+#[should_panic(expected = "insufficient allowance")]
+fn test_transfer_from_exceeding_allowance_panics() {
+    let (env, client, admin) = setup();
+    client.initialize(&admin, &String::from_str(&env, "Token"), &String::from_str(&env, "TKN"));
+
+    let alice = Address::generate(&env);
+    let spender = Address::generate(&env);
+    client.mint(&alice, &1000);
+    client.approve(&alice, &spender, &50, &200);
+    client.transfer_from(&spender, &alice, &spender, &51);
+}
+
+#[test]
+fn test_allowance_expires_after_ledger() {
+    let (env, client, admin) = setup();
+    client.initialize(&admin, &String::from_str(&env, "Token"), &String::from_str(&env, "TKN"));
+
+    let alice = Address::generate(&env);
+    let spender = Address::generate(&env);
+    env.ledger().set_sequence_number(100);
+    client.approve(&alice, &spender, &500, &150);
+
+    // Son kullanma ledger'ı dahil geçerli
+    env.ledger().set_sequence_number(150);
+    assert_eq!(client.allowance(&alice, &spender), 500);
+
+    // Sonrasında izin 0 okunur
+    env.ledger().set_sequence_number(151);
+    assert_eq!(client.allowance(&alice, &spender), 0);
+}
+
+#[test]
+#[should_panic(expected = "expiration_ledger is less than ledger seq")]
+fn test_approve_past_expiration_panics() {
+    let (env, client, admin) = setup();
+    client.initialize(&admin, &String::from_str(&env, "Token"), &String::from_str(&env, "TKN"));
+
+    let alice = Address::generate(&env);
+    let spender = Address::generate(&env);
+    env.ledger().set_sequence_number(100);
+    client.approve(&alice, &spender, &500, &99);
+}
+
+#[test]
+fn test_approve_zero_with_past_expiration_revokes() {
+    let (env, client, admin) = setup();
+    client.initialize(&admin, &String::from_str(&env, "Token"), &String::from_str(&env, "TKN"));
+
+    let alice = Address::generate(&env);
+    let spender = Address::generate(&env);
+    env.ledger().set_sequence_number(100);
+    client.approve(&alice, &spender, &500, &200);
+    // Sıfır tutarlı approve geçmiş ledger ile de kabul edilir (iptal)
+    client.approve(&alice, &spender, &0, &0);
+    assert_eq!(client.allowance(&alice, &spender), 0);
+}
+
+// ═══════════════════════════════════════════════════
+//  BURN TESTLERİ
+// ═══════════════════════════════════════════════════
+
+#[test]
+fn test_burn_reduces_balance() {
+    let (env, client, admin) = setup();
+    client.initialize(&admin, &String::from_str(&env, "Token"), &String::from_str(&env, "TKN"));
+
+    let alice = Address::generate(&env);
+    client.mint(&alice, &1000);
+    client.burn(&alice, &250);
+
+    // SEP-41: ("burn", from) → amount
+    let last = env.events().all().last().unwrap();
+    let topic_symbol: Symbol = last.1.get(0).unwrap().into_val(&env);
+    let amount: i128 = last.2.into_val(&env);
+    assert_eq!(topic_symbol, Symbol::new(&env, "burn"));
+    assert_eq!(amount, 250);
+    assert_eq!(client.balance(&alice), 750);
+}
+
+#[test]
+fn test_burn_from_spends_allowance() {
+    let (env, client, admin) = setup();
+    client.initialize(&admin, &String::from_str(&env, "Token"), &String::from_str(&env, "TKN"));
+
+    let alice = Address::generate(&env);
+    let spender = Address::generate(&env);
+    client.mint(&alice, &1000);
+    client.approve(&alice, &spender, &400, &200);
+    client.burn_from(&spender, &alice, &150);
+
+    assert_eq!(client.balance(&alice), 850);
+    assert_eq!(client.allowance(&alice, &spender), 250);
+}
+
+#[test]
+#[should_panic(expected = "insufficient allowance")]
+fn test_burn_from_without_allowance_panics() {
+    let (env, client, admin) = setup();
+    client.initialize(&admin, &String::from_str(&env, "Token"), &String::from_str(&env, "TKN"));
+
+    let alice = Address::generate(&env);
+    let spender = Address::generate(&env);
+    client.mint(&alice, &1000);
+    client.burn_from(&spender, &alice, &1);
+}
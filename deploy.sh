@@ -154,6 +154,8 @@ fi
 deploy_contract() {
   local name="$1"
   local wasm="$2"
+  # Kalan argümanlar constructor'a iletilir (ör. --admin <adres>)
+  local ctor_args=("${@:3}")
 
   if [[ "$ACTION" == "upgrade" ]]; then
     # Upgrade requires existing contract ID from env
@@ -168,19 +170,35 @@ deploy_contract() {
       fail "Cannot upgrade: $env_key not found in frontend/.env. Deploy first."
     fi
 
-    log "Upgrading $name contract ($existing_id)..."
-    stellar contract upload \
+    log "Upgrading $name contract ($existing_id)..." >&2
+    local wasm_hash
+    wasm_hash=$(stellar contract upload \
       --wasm "$wasm" \
       --source "$IDENTITY_NAME" \
-      --network "$NETWORK" > /dev/null
+      --network "$NETWORK") || fail "Upload of $name WASM failed"
     stellar contract invoke \
       --id "$existing_id" \
       --source "$IDENTITY_NAME" \
       --network "$NETWORK" \
-      -- upgrade --new-wasm-hash "$(stellar contract install --wasm "$wasm" --source "$IDENTITY_NAME" --network "$NETWORK")" 2>&1 || {
-      warn "Upgrade via invoke failed — contract may not support upgrade(). Re-deploying instead."
+      -- upgrade --caller "$DEPLOYER_ADDR" --new-wasm-hash "$wasm_hash" >&2 || {
+      warn "Upgrade via invoke failed — contract may not support upgrade(). Re-deploying instead." >&2
       ACTION="deploy"
     }
+    # Şema değiştiyse depolama partiler halinde taşınır; migrate devam konumu yerine
+    # null döndürene kadar çağrılır
+    if [[ "$ACTION" == "upgrade" && "$name" == "stellar_split" ]]; then
+      local cursor
+      while :; do
+        cursor=$(stellar contract invoke \
+          --id "$existing_id" \
+          --source "$IDENTITY_NAME" \
+          --network "$NETWORK" \
+          -- migrate --caller "$DEPLOYER_ADDR" --limit 25) || fail "Migration of $name failed"
+        [[ -z "$cursor" || "$cursor" == "null" ]] && break
+        log "Migration in progress, next position: $cursor" >&2
+      done
+      ok "Storage migrated to the current schema" >&2
+    fi
     if [[ "$ACTION" == "upgrade" ]]; then
       echo "$existing_id"
       return
@@ -192,12 +210,13 @@ deploy_contract() {
   contract_id=$(stellar contract deploy \
     --wasm "$wasm" \
     --source "$IDENTITY_NAME" \
-    --network "$NETWORK" 2>&1) || fail "Deployment of $name failed:\n$contract_id"
+    --network "$NETWORK" \
+    ${ctor_args[@]+-- "${ctor_args[@]}"} 2>&1) || fail "Deployment of $name failed:\n$contract_id"
 
   echo "$contract_id"
 }
 
-SPLIT_CONTRACT_ID=$(deploy_contract "stellar_split" "$WASM_SPLIT")
+SPLIT_CONTRACT_ID=$(deploy_contract "stellar_split" "$WASM_SPLIT" --admin "$(stellar keys address "$IDENTITY_NAME")")
 ok "stellar_split deployed: $SPLIT_CONTRACT_ID"
 
 TOKEN_CONTRACT_ID=""
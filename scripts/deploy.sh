@@ -1,6 +1,7 @@
 #!/bin/bash
 # StellarSplit — Contract Deploy Script
-# Kullanım: bash scripts/deploy.sh
+# Kullanım: bash scripts/deploy.sh                      # yeni deploy
+#           bash scripts/deploy.sh upgrade <CONTRACT_ID> # mevcut kontratı yükselt
 
 set -e
 
@@ -17,15 +18,56 @@ fi
 
 echo "📦 WASM size: $(du -h $WASM_PATH | cut -f1)"
 
+if [ "$1" = "upgrade" ]; then
+    CONTRACT_ID="$2"
+    if [ -z "$CONTRACT_ID" ]; then
+        echo "❌ Usage: bash scripts/deploy.sh upgrade <CONTRACT_ID>"
+        exit 1
+    fi
+    ADMIN="$(stellar keys address alice)"
+
+    echo ""
+    echo "⬆️  Upgrading $CONTRACT_ID on Stellar Testnet..."
+    WASM_HASH=$(stellar contract upload \
+        --wasm "$WASM_PATH" \
+        --network testnet \
+        --source-account alice)
+    stellar contract invoke \
+        --id "$CONTRACT_ID" \
+        --network testnet \
+        --source-account alice \
+        -- upgrade --caller "$ADMIN" --new_wasm_hash "$WASM_HASH"
+
+    # Depolama partiler halinde taşınır; migrate null döndürene kadar tekrarlanır
+    echo "🗄️  Migrating storage..."
+    while true; do
+        CURSOR=$(stellar contract invoke \
+            --id "$CONTRACT_ID" \
+            --network testnet \
+            --source-account alice \
+            -- migrate --caller "$ADMIN" --limit 25)
+        if [ -z "$CURSOR" ] || [ "$CURSOR" = "null" ]; then
+            break
+        fi
+        echo "   next position: $CURSOR"
+    done
+
+    echo ""
+    echo "✅ Contract upgraded and migrated!"
+    exit 0
+fi
+
 echo ""
 echo "🚀 Deploying to Stellar Testnet..."
 echo ""
 
 # Testnet'e deploy
+# Kontrat yöneticisi constructor argümanıdır; deploy eden hesap yönetici olur
 stellar contract deploy \
     --wasm "$WASM_PATH" \
     --network testnet \
-    --source-account alice
+    --source-account alice \
+    -- --admin "$(stellar keys address alice)"
 
 echo ""
 echo "✅ Contract deployed!"